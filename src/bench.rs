@@ -1,44 +1,89 @@
-mod game;
-mod gameboards;
-
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use game::Game;
-use gameboards::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+use sudoku::gameboards::*;
+use sudoku::Game;
 
 fn bench_easy(c: &mut Criterion) {
     c.bench_function("easy", move |b| {
-        b.iter_with_setup(|| Game::new(EASY), |mut game| black_box(game.solve()));
+        b.iter_with_setup(|| Game::new(EASY), |mut game| game.solve());
     });
 }
 
 fn bench_medium(c: &mut Criterion) {
     c.bench_function("medium", move |b| {
-        b.iter_with_setup(|| Game::new(MEDIUM), |mut game| black_box(game.solve()));
+        b.iter_with_setup(|| Game::new(MEDIUM), |mut game| game.solve());
     });
 }
 
 fn bench_hard(c: &mut Criterion) {
     c.bench_function("hard", move |b| {
-        b.iter_with_setup(|| Game::new(HARD), |mut game| black_box(game.solve()));
+        b.iter_with_setup(|| Game::new(HARD), |mut game| game.solve());
     });
 }
 
 fn bench_seventeen(c: &mut Criterion) {
     c.bench_function("seventeen", move |b| {
-        b.iter_with_setup(|| Game::new(SEVENTEEN), |mut game| black_box(game.solve()));
+        b.iter_with_setup(|| Game::new(SEVENTEEN), |mut game| game.solve());
     });
 }
 
 fn bench_zeros(c: &mut Criterion) {
     c.bench_function("zeros", move |b| {
-        b.iter_with_setup(|| Game::new(ZEROS), |mut game| black_box(game.solve()));
+        b.iter_with_setup(|| Game::new(ZEROS), |mut game| game.solve());
+    });
+}
+
+fn bench_ai_escargot(c: &mut Criterion) {
+    c.bench_function("ai_escargot", move |b| {
+        b.iter_with_setup(|| Game::new(AI_ESCARGOT), |mut game| game.solve());
+    });
+}
+
+fn bench_easter_monster(c: &mut Criterion) {
+    c.bench_function("easter_monster", move |b| {
+        b.iter_with_setup(|| Game::new(EASTER_MONSTER), |mut game| game.solve());
+    });
+}
+
+fn bench_platinum_blonde(c: &mut Criterion) {
+    c.bench_function("platinum_blonde", move |b| {
+        b.iter_with_setup(|| Game::new(PLATINUM_BLONDE), |mut game| game.solve());
+    });
+}
+
+// Taking each clue out of a puzzle in turn, the way a generator tries out smaller puzzles, first by
+// rebuilding the board and then with `without_clue`.
+fn bench_rebuild_without_clue(c: &mut Criterion) {
+    c.bench_function("rebuild_without_clue", move |b| {
+        b.iter(|| {
+            for (y, x) in (0..81).map(|k| (k / 9, k % 9)) {
+                if SEVENTEEN[y][x] != 0 {
+                    let mut numbers = SEVENTEEN;
+                    numbers[y][x] = 0;
+                    criterion::black_box(Game::new(numbers));
+                }
+            }
+        });
+    });
+}
+
+fn bench_without_clue(c: &mut Criterion) {
+    let game = Game::new(SEVENTEEN);
+    c.bench_function("without_clue", move |b| {
+        b.iter(|| {
+            for (y, x) in (0..81).map(|k| (k / 9, k % 9)) {
+                if SEVENTEEN[y][x] != 0 {
+                    criterion::black_box(game.without_clue((y, x)).ok());
+                }
+            }
+        });
     });
 }
 
 criterion_group! {
     name = bench;
     config = Criterion::default();
-    targets = bench_easy, bench_medium, bench_hard, bench_seventeen, bench_zeros
+    targets = bench_easy, bench_medium, bench_hard, bench_seventeen, bench_zeros, bench_ai_escargot,
+        bench_easter_monster, bench_platinum_blonde, bench_rebuild_without_clue, bench_without_clue
 }
 
 criterion_main!(bench);
@@ -0,0 +1,158 @@
+// A quick, fixed benchmark of the backtracking solver, for telling at a glance whether a build is
+// as fast as it should be, like a debug build shipped by mistake or a real slowdown.
+
+use crate::game::SearchHooks;
+use crate::gameboards;
+use crate::{Game, SolverConfig, SplitMix64, SudokuRng, Transform};
+use std::fmt;
+use std::sync::atomic::AtomicUsize;
+use std::time::{Duration, Instant};
+
+/// The version of the workload `self_benchmark` solves. Any change to which puzzles are in it
+/// bumps this, so only reports with the same version are worth comparing.
+pub const WORKLOAD_VERSION: u32 = 2;
+
+// How many rearranged boards the workload adds to the presets, and the seed they're made from.
+const REARRANGED: usize = 24;
+const SEED: u64 = 0x5eed_0001;
+
+/// The results of `self_benchmark`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SelfBenchReport {
+    pub workload_version: u32,
+    pub puzzles: usize,
+    /// Search tree nodes visited over every puzzle
+    pub nodes: usize,
+    pub elapsed: Duration,
+    /// Whether this is a build with debug assertions on, which is usually many times slower
+    pub debug_build: bool,
+}
+
+impl SelfBenchReport {
+    pub fn nodes_per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.nodes as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for SelfBenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "workload v{}: {} puzzles",
+            self.workload_version, self.puzzles
+        )?;
+        writeln!(f, "nodes: {}", self.nodes)?;
+        writeln!(f, "time: {:.1?}", self.elapsed)?;
+        writeln!(f, "nodes/second: {:.0}", self.nodes_per_second())?;
+        writeln!(
+            f,
+            "build: {}",
+            if self.debug_build { "debug" } else { "release" }
+        )
+    }
+}
+
+// Every board in `gameboards`, then rearrangements of the hard ones that come with solutions,
+// picked with a fixed seed so the workload is the same on every machine.
+fn workload() -> Vec<Game> {
+    let presets = gameboards::with_solutions();
+    let mut games = presets
+        .iter()
+        .map(|named| Game::new(named.board))
+        .collect::<Vec<_>>();
+    let sources = presets
+        .iter()
+        .filter(|named| named.solution.is_some())
+        .map(|named| Game::new(named.board))
+        .collect::<Vec<_>>();
+    let mut rng = SplitMix64::new(SEED);
+    for k in 0..REARRANGED {
+        let transform = random_transform(&mut rng);
+        games.push(sources[k % sources.len()].transformed(&transform));
+    }
+    games
+}
+
+fn shuffle<T>(items: &mut [T], rng: &mut impl SudokuRng) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.below(i + 1));
+    }
+}
+
+// A rearrangement that shuffles the bands, the rows within each band, the stacks, the columns
+// within each stack, and the values, and transposes half the time.
+fn random_transform(rng: &mut impl SudokuRng) -> Transform {
+    let mut lines = || {
+        let mut bands = [0, 1, 2];
+        shuffle(&mut bands, rng);
+        let mut order = [0; 9];
+        for (i, &band) in bands.iter().enumerate() {
+            let mut within = [0, 1, 2];
+            shuffle(&mut within, rng);
+            for (j, &line) in within.iter().enumerate() {
+                order[3 * i + j] = 3 * band + line;
+            }
+        }
+        order
+    };
+    let rows = lines();
+    let cols = lines();
+    let mut values = Transform::identity().values;
+    shuffle(&mut values, rng);
+    Transform {
+        transpose: rng.below(2) == 1,
+        rows,
+        cols,
+        values,
+    }
+}
+
+/// Solves a fixed set of puzzles, the boards in `gameboards` and some rearrangements of them, and
+/// reports how fast the search went. The search branches on the cell with the fewest candidates,
+/// as with `SolverConfig::any_order`, which keeps the total time down to a second or so in a
+/// release build.
+pub fn self_benchmark() -> SelfBenchReport {
+    let games = workload();
+    let nodes = AtomicUsize::new(0);
+    let hooks = SearchHooks {
+        config: SolverConfig {
+            any_order: true,
+            ..SolverConfig::default()
+        },
+        nodes: Some(&nodes),
+        ..SearchHooks::default()
+    };
+    let start = Instant::now();
+    for game in &games {
+        let mut game = *game;
+        let _ = game.solve_hooked(&hooks);
+    }
+    SelfBenchReport {
+        workload_version: WORKLOAD_VERSION,
+        puzzles: games.len(),
+        nodes: nodes.into_inner(),
+        elapsed: start.elapsed(),
+        debug_build: cfg!(debug_assertions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{self_benchmark, WORKLOAD_VERSION};
+    use std::time::Duration;
+
+    // Timing depends on the machine, so this only runs when asked for, with
+    // `cargo test --release -- --ignored`. The bound is many times what a release build takes.
+    #[test]
+    #[ignore]
+    fn self_benchmark_finishes_promptly() {
+        let report = self_benchmark();
+        assert_eq!(report.workload_version, WORKLOAD_VERSION);
+        assert_eq!(report.puzzles, 36);
+        assert!(report.nodes > 0);
+        assert!(report.elapsed < Duration::from_secs(20), "{}", report);
+    }
+}
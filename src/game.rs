@@ -1,4 +1,45 @@
+mod bands;
+mod budget;
+mod builder;
+mod candidates;
+mod clues;
+mod digits;
+mod draft;
+mod editor;
+mod generate;
+mod logic;
+mod positions;
+mod puzzle;
+mod random;
+mod state;
+mod text;
+mod transform;
+mod unavoidable;
+
+pub use bands::{band_completions, Band};
+pub use budget::{HintBudget, HintKind, HintsExhausted};
+pub use builder::GameBuilder;
+pub use candidates::Candidates;
+pub use clues::{ClueReport, MaskError};
+pub use digits::DigitArray;
+pub use draft::{DraftBoard, Violation};
+pub use editor::{PuzzleEditor, Status};
+pub use logic::{
+    ChainLimits, Contradiction, Difficulty, ForcingChain, Hint, HintOutcome, Rating,
+    RestrictedSolve, SolverPipeline, Step, Technique, TechniqueSet, UnknownTechnique,
+};
+pub use positions::{DigitSummary, PositionMask};
+pub use puzzle::{Puzzle, Solution};
+pub use random::{SplitMix64, SudokuRng};
+pub use state::{SaveState, StateBlob};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::error::Error;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+pub use text::{quick_validate, BoardDisplay, ParseError};
+pub(crate) use transform::hash_form;
+pub use transform::Transform;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -35,44 +76,429 @@ impl CellValue {
     }
 }
 
-impl From<CellValue> for usize {
-    fn from(other: CellValue) -> Self {
-        (other as u8 - 1) as usize
+/// Reasons a board can't be turned into a `Game`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BoardError {
+    /// A cell held a number other than 0-9
+    InvalidValue { row: usize, col: usize, value: u8 },
+    /// Some row, column, or 3x3 has a repeated value, or an empty cell has no possible values
+    Invalid,
+    /// Saved solver state was truncated or its parts don't agree with each other
+    CorruptState,
+    /// The board has empty cells where a full one was needed
+    Incomplete,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::InvalidValue { row, col, value } => {
+                write!(
+                    f,
+                    "cell {} has invalid value {}",
+                    Game::cell_name(*row, *col),
+                    value
+                )
+            }
+            BoardError::Invalid => write!(f, "board breaks the rules of sudoku"),
+            BoardError::CorruptState => write!(f, "saved solver state is corrupt"),
+            BoardError::Incomplete => write!(f, "board has empty cells"),
+        }
+    }
+}
+
+impl Error for BoardError {}
+
+/// Reasons a value can't be placed in a cell.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PlaceError {
+    /// The coordinates are outside the board
+    OutOfBounds,
+    /// The cell already has a value
+    Filled,
+    /// Another cell in the same row, column, or 3x3 already has the value
+    Conflict { row: usize, col: usize },
+}
+
+impl fmt::Display for PlaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlaceError::OutOfBounds => write!(f, "cell is outside the board"),
+            PlaceError::Filled => write!(f, "cell already has a value"),
+            PlaceError::Conflict { row, col } => {
+                write!(
+                    f,
+                    "cell {} already has that value",
+                    Game::cell_name(*row, *col)
+                )
+            }
+        }
+    }
+}
+
+impl Error for PlaceError {}
+
+/// Reasons a clue can't be taken out of a game.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EraseError {
+    /// The coordinates are outside the board
+    OutOfBounds,
+    /// The cell has no value to take out
+    Empty,
+}
+
+impl fmt::Display for EraseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EraseError::OutOfBounds => write!(f, "cell is outside the board"),
+            EraseError::Empty => write!(f, "cell has no value"),
+        }
+    }
+}
+
+impl Error for EraseError {}
+
+/// Reasons a cell can't be revealed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RevealError {
+    /// The coordinates are outside the board
+    OutOfBounds,
+    /// The cell is a given, so there's nothing to reveal
+    Given,
+    /// The givens have no solution
+    Unsolvable,
+    /// The givens have more than one solution, so the cell has no one right value
+    NotUnique,
+}
+
+impl fmt::Display for RevealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RevealError::OutOfBounds => write!(f, "cell is outside the board"),
+            RevealError::Given => write!(f, "cell is a given"),
+            RevealError::Unsolvable => write!(f, "puzzle has no solution"),
+            RevealError::NotUnique => write!(f, "puzzle has more than one solution"),
+        }
+    }
+}
+
+impl Error for RevealError {}
+
+/// What `Game::reveal` did.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reveal {
+    pub value: CellValue,
+    /// A different value the player had in the cell, which the right one replaced
+    pub replaced: Option<CellValue>,
+    /// Cells the player filled in with the same value that saw the revealed cell, which were
+    /// emptied so the board doesn't break the rules
+    pub cleared: Vec<Coord>,
+}
+
+/// Reasons a player can't cross off or restore a candidate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ElimError {
+    /// The coordinates are outside the board
+    OutOfBounds,
+    /// The cell already has a value
+    Filled,
+    /// The candidate being restored was never crossed off
+    NotCrossedOff,
+}
+
+impl fmt::Display for ElimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElimError::OutOfBounds => write!(f, "cell is outside the board"),
+            ElimError::Filled => write!(f, "cell already has a value"),
+            ElimError::NotCrossedOff => write!(f, "candidate wasn't crossed off"),
+        }
+    }
+}
+
+impl Error for ElimError {}
+
+/// Reasons the solver can fail to produce a solution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// The game has no solution
+    Unsolvable,
+    /// The board already breaks the rules, so there was nothing to search
+    Invalid,
+    /// The search was cancelled before it finished
+    Cancelled,
+    /// A breadth-first search had more games waiting than it was allowed to keep
+    FrontierFull,
+    /// A solve from `spawn_solve` panicked on its thread, which is a bug in the solver
+    Panicked,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Unsolvable => write!(f, "game has no solution"),
+            SolveError::Invalid => write!(f, "board breaks the rules of sudoku"),
+            SolveError::Cancelled => write!(f, "solve was cancelled"),
+            SolveError::FrontierFull => write!(f, "search frontier grew past its limit"),
+            SolveError::Panicked => write!(f, "solver panicked"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Options for the backtracking search.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolverConfig {
+    /// When branching, only try one of the values that don't appear anywhere on the board yet,
+    /// since swapping those values around in a solution always gives another solution. This can
+    /// prune most of the search on sparse boards, but it changes which solution is found first,
+    /// and solution counts only include one solution out of each group of swapped ones. Values
+    /// crossed off an empty cell tell the others apart, so this does nothing while there are any.
+    pub break_symmetry: bool,
+    /// Give up the guarantee that solutions come out in order, which lets the search branch on
+    /// whichever cell has the fewest possible values instead of always the first empty one.
+    pub any_order: bool,
+}
+
+pub(crate) type SearchCallback<'a> = dyn FnMut(&Game, usize) + 'a;
+
+// The search configuration, plus ways to watch a search and stop it from another thread.
+#[derive(Default)]
+pub(crate) struct SearchHooks<'a> {
+    pub(crate) config: SolverConfig,
+    pub(crate) cancel: Option<&'a AtomicBool>,
+    pub(crate) nodes: Option<&'a AtomicUsize>,
+    // Shown each state the search reaches, along with how deep in the search it is
+    pub(crate) callback: Option<&'a RefCell<SearchCallback<'a>>>,
+}
+
+impl SearchHooks<'_> {
+    // Called for every node of the search tree before it's expanded.
+    fn visit(&self) -> Result<(), SolveError> {
+        if let Some(nodes) = self.nodes {
+            nodes.fetch_add(1, Ordering::Relaxed);
+        }
+        match self.cancel {
+            Some(cancel) if cancel.load(Ordering::Relaxed) => Err(SolveError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A rough measure of how much searching a game will need, from `Game::estimate_hardness`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HardnessEstimate {
+    /// Cells still empty once propagation gets stuck
+    pub empty_cells: usize,
+    /// Total possible values across those empty cells
+    pub candidates: usize,
+    /// Base 10 logarithm of the product of the empty cells' possible value counts, which is an
+    /// upper bound on the number of boards a search would have to look through. If an empty cell
+    /// has no possible values left, there's nothing to search and this is negative infinity, the
+    /// logarithm of 0.
+    pub log_search_space: f64,
+}
+
+/// What `Game::propagate` did.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PropagationReport {
+    /// Cells filled in, in reading order
+    pub placements: Vec<(usize, usize, CellValue)>,
+    /// Candidates that went away from cells that are still empty
+    pub eliminations: Vec<(usize, usize, CellValue)>,
+    /// Sweeps over the board that changed something
+    pub passes: usize,
+    /// Whether the board ended up full
+    pub solved: bool,
+}
+
+/// A cell position as `(row, col)`, both 0-based.
+pub type Coord = (usize, usize);
+
+/// The consequences of a move, from `Game::preview`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Preview {
+    /// Filled cells that already have the value in the same row, column, or 3x3
+    pub conflicts: Vec<Coord>,
+    /// Empty cells that would be left with only one possible value, and that value
+    pub newly_forced: Vec<(usize, usize, CellValue)>,
+}
+
+/// A node of the search tree explored by `Game::solve_tree`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchNode {
+    /// The cell the search branched on to get here, or `None` at the root
+    pub cell: Option<Coord>,
+    /// The value tried in that cell
+    pub value: Option<CellValue>,
+    /// The branches tried below this one, in order. Only the last can have led to a solution.
+    pub children: Vec<SearchNode>,
+    /// Whether a solution was found below this node
+    pub solved: bool,
+    /// Whether the node cap ran out before every branch here could be tried
+    pub truncated: bool,
+}
+
+/// An iterator over the solutions to a game, from `Game::solutions`.
+pub struct Solutions {
+    // Games still to search, with the next one to look at on top
+    stack: Vec<Game>,
+    config: SolverConfig,
+}
+
+impl Iterator for Solutions {
+    type Item = Game;
+
+    fn next(&mut self) -> Option<Game> {
+        while let Some(mut game) = self.stack.pop() {
+            game.propagate_to_fixpoint();
+            if !game.is_valid(false) || !game.units_completable() {
+                continue;
+            }
+            let (y, x, poss) = match game.branch_cell(&self.config) {
+                Some(cell) => cell,
+                None => return Some(game),
+            };
+            // Push the branches largest value first so the smallest gets searched first.
+            for cv in game.branch_values(&poss, &self.config).into_iter().rev() {
+                let mut new = game;
+                new.set_cell(y, x, cv);
+                self.stack.push(new);
+            }
+        }
+        None
     }
 }
 
+// Every other cell in the same row, column, or 3x3 as the given cell.
+fn peers(row: usize, col: usize) -> impl Iterator<Item = Coord> {
+    (0..9)
+        .flat_map(|y| (0..9).map(move |x| (y, x)))
+        .filter(move |&cell| Game::are_peers(cell, (row, col)))
+}
+
+/// A game of sudoku: the board along with what's known about each empty cell.
+///
+/// A `Game` is only plain arrays, so it's `Send` and `Sync` and can be shared between threads as
+/// it is, for example behind an `Arc` or a read lock, with `solve_into` solving from the shared
+/// copy without writing to it.
 #[derive(Copy, Clone)]
 // Each board is an array of rows (reverse coordinates, (y, x))
 pub struct Game {
     board: [[Option<CellValue>; 9]; 9],
     cell_poss: [[[bool; 9]; 9]; 9],
-    pub cols_flags: [[bool; 9]; 9],
-    pub rows_flags: [[bool; 9]; 9],
-    pub sqrs_flags: [[bool; 9]; 9],
+    // Possible values crossed off by logical techniques, on top of what the flags rule out
+    elims: [[Candidates; 9]; 9],
+    // Possible values crossed off by the player. Unlike `elims` these never depend on other
+    // cells, so they survive cells being erased.
+    crossed_off: [[Candidates; 9]; 9],
+    // Cells filled in when the game was made, as opposed to by a player or the solver
+    givens: [[bool; 9]; 9],
+    pub cols_flags: [DigitArray<bool>; 9],
+    pub rows_flags: [DigitArray<bool>; 9],
+    pub sqrs_flags: [DigitArray<bool>; 9],
+    // For each unit and value, a bitmask of the empty cells in the unit that can take the value,
+    // kept in step with `cell_poss`
+    positions: [[u16; 9]; 27],
+    // The known solution, which every logical step gets checked against
+    #[cfg(feature = "paranoid")]
+    oracle: Option<[[u8; 9]; 9]>,
 }
 
+// Keeps `Game` shareable between threads, so a field that isn't can't be added by accident.
+const _: () = {
+    const fn shareable<T: Send + Sync>() {}
+    shareable::<Game>();
+};
+
 impl Game {
     pub fn new(numbers: [[u8; 9]; 9]) -> Self {
+        assert!(numbers.iter().flatten().all(|&n| n < 10));
+        let new = Game::from_numbers(numbers);
+        assert!(new.is_valid(true));
+        new
+    }
+
+    /// Like `new`, but returns an error instead of panicking if a cell holds a number other than
+    /// 0-9 or the board breaks the rules.
+    pub fn try_new(numbers: [[u8; 9]; 9]) -> Result<Self, BoardError> {
+        for (y, row) in numbers.iter().enumerate() {
+            if let Some((x, &value)) = row.iter().enumerate().find(|&(_, &n)| n >= 10) {
+                return Err(BoardError::InvalidValue {
+                    row: y,
+                    col: x,
+                    value,
+                });
+            }
+        }
+        let new = Game::from_numbers(numbers);
+        if new.is_valid(false) {
+            Ok(new)
+        } else {
+            Err(BoardError::Invalid)
+        }
+    }
+
+    /// Builds a game where each cell starts with a set of allowed values instead of a single
+    /// given, as a bitmask with bit `i` for the value `i + 1`. A mask with one bit is a given, and
+    /// a cell with all nine bits is unrestricted. Fails if a mask is 0 or has bits above the ninth,
+    /// or if the restrictions break the rules.
+    pub fn new_with_restrictions(restrictions: [[u16; 9]; 9]) -> Result<Self, BoardError> {
+        let mut builder = GameBuilder::new();
+        let mut givens = [[0; 9]; 9];
+        for (y, row) in restrictions.iter().enumerate() {
+            for (x, &mask) in row.iter().enumerate() {
+                if mask == 0 || mask & !0x1ff != 0 {
+                    return Err(BoardError::Invalid);
+                }
+                if mask.count_ones() == 1 {
+                    givens[y][x] = CellValue::from_index(mask.trailing_zeros() as usize) as u8;
+                } else if mask != 0x1ff {
+                    let candidates = (0..9)
+                        .filter(|i| mask & 1 << i != 0)
+                        .map(CellValue::from_index)
+                        .collect();
+                    builder = builder.candidates((y, x), candidates);
+                }
+            }
+        }
+        builder.givens(givens).build()
+    }
+
+    /// Builds a game from arbitrary bytes for fuzzing, taking each of the first 81 bytes modulo
+    /// 10 as a cell, row by row. This never panics: too few bytes or a board that breaks the rules
+    /// gives `None`.
+    pub fn from_fuzz_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < 81 {
+            return None;
+        }
+        let mut numbers = [[0; 9]; 9];
+        for (i, &byte) in data[..81].iter().enumerate() {
+            numbers[i / 9][i % 9] = byte % 10;
+        }
+        Game::try_new(numbers).ok()
+    }
+
+    fn from_numbers(numbers: [[u8; 9]; 9]) -> Self {
         let mut board = [[None; 9]; 9];
         let mut cell_poss = [[[true; 9]; 9]; 9];
         // Arrays of markers for whether each group has a cell value yet
-        let mut rows_flags = [[false; 9]; 9];
-        let mut cols_flags = [[false; 9]; 9];
-        let mut sqrs_flags = [[false; 9]; 9];
+        let mut rows_flags = [DigitArray([false; 9]); 9];
+        let mut cols_flags = [DigitArray([false; 9]); 9];
+        let mut sqrs_flags = [DigitArray([false; 9]); 9];
         for (y, row) in rows_flags.iter_mut().enumerate() {
             for (x, col) in cols_flags.iter_mut().enumerate() {
-                let n = numbers[y][x];
-                assert!(n < 10);
-                if let Some(cv) = CellValue::new(n) {
+                if let Some(cv) = CellValue::new(numbers[y][x]) {
                     // Mark everything but the stored value impossible
-                    for i in (0..9).filter(|&i| i != n as usize - 1) {
+                    for i in (0..9).filter(|&i| i != usize::from(cv)) {
                         cell_poss[y][x][i] = false;
                     }
                     board[y][x] = Some(cv);
                     let s = 3 * (y / 3) + x / 3;
-                    row[n as usize - 1] = true;
-                    col[n as usize - 1] = true;
-                    sqrs_flags[s][n as usize - 1] = true;
+                    row[cv] = true;
+                    col[cv] = true;
+                    sqrs_flags[s][cv] = true;
                 }
             }
         }
@@ -81,22 +507,361 @@ impl Game {
         for (y, row) in rows_flags.iter().enumerate() {
             for (x, col) in cols_flags.iter().enumerate() {
                 if board[y][x].is_none() {
-                    for i in 0..9 {
+                    for (i, &cv) in CellValue::ALL.iter().enumerate() {
                         let s = 3 * (y / 3) + x / 3;
-                        cell_poss[y][x][i] = !(row[i] || col[i] || sqrs_flags[s][i]);
+                        cell_poss[y][x][i] = !(row[cv] || col[cv] || sqrs_flags[s][cv]);
                     }
                 }
             }
         }
-        let new = Game {
+        let mut game = Game {
             board,
             cell_poss,
+            elims: [[Candidates::empty(); 9]; 9],
+            crossed_off: [[Candidates::empty(); 9]; 9],
+            givens: numbers.map(|row| row.map(|n| n != 0)),
             cols_flags,
             rows_flags,
             sqrs_flags,
+            positions: [[0; 9]; 27],
+            #[cfg(feature = "paranoid")]
+            oracle: None,
         };
-        assert!(new.is_valid(true));
-        new
+        game.sync_positions();
+        game
+    }
+
+    /// The board as plain numbers, the same layout `new` takes, with 0 for empty cells.
+    pub fn to_array(&self) -> [[u8; 9]; 9] {
+        let mut numbers = [[0; 9]; 9];
+        for (y, x, cell) in self.iter_cells() {
+            numbers[y][x] = cell.map_or(0, |cv| cv as u8);
+        }
+        numbers
+    }
+
+    /// The filled cells as one bitboard per value, indexed by value minus 1. Bit `9 * row + col`
+    /// is set where the value is placed, and the top 47 bits are always clear.
+    pub fn value_bitboards(&self) -> [u128; 9] {
+        let mut boards = [0; 9];
+        for (y, x, cell) in self.iter_cells() {
+            if let Some(cv) = *cell {
+                boards[usize::from(cv)] |= 1 << Game::index(y, x);
+            }
+        }
+        boards
+    }
+
+    /// Builds a game from bitboards laid out like `value_bitboards`. Fails if a cell is set on
+    /// more than one bitboard, if a bit above the 81st is set, or if the board breaks the rules.
+    pub fn from_value_bitboards(boards: [u128; 9]) -> Result<Self, BoardError> {
+        let mut numbers = [[0; 9]; 9];
+        let mut seen = 0;
+        for (i, &board) in boards.iter().enumerate() {
+            if board >> 81 != 0 || board & seen != 0 {
+                return Err(BoardError::Invalid);
+            }
+            seen |= board;
+            for k in (0..81).filter(|&k| board & 1 << k != 0) {
+                numbers[k / 9][k % 9] = CellValue::from_index(i) as u8;
+            }
+        }
+        Game::try_new(numbers)
+    }
+
+    /// Names a cell in the usual notation for people, with rows and columns counted from 1:
+    /// `R1C1` is the top left cell and `R9C9` the bottom right. Everything this crate shows
+    /// people names cells this way.
+    pub fn cell_name(row: usize, col: usize) -> String {
+        format!("R{}C{}", row + 1, col + 1)
+    }
+
+    /// Reads a cell name like `R3C7` back into a row and column counted from 0. Lower case is
+    /// accepted too. Returns `None` for anything else, including rows or columns outside 1 to 9.
+    pub fn parse_cell_name(name: &str) -> Option<Coord> {
+        let index = |b: u8| CellValue::from_char(char::from(b)).map(usize::from);
+        match name.as_bytes() {
+            &[r, row, c, col] if r.eq_ignore_ascii_case(&b'r') && c.eq_ignore_ascii_case(&b'c') => {
+                Some((index(row)?, index(col)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// The position of a cell in a list of all 81, row by row: `row * 9 + col`. Only meaningful
+    /// for a row and column below 9, which give an index below 81.
+    pub fn index(row: usize, col: usize) -> usize {
+        row * 9 + col
+    }
+
+    /// The row and column of the cell at an index from `Game::index`. Only meaningful for an
+    /// index below 81.
+    pub fn coords(index: usize) -> Coord {
+        (index / 9, index % 9)
+    }
+
+    /// Whether two cells share a row, column, or 3x3, so they can't hold the same value. A cell
+    /// isn't its own peer.
+    pub fn are_peers(a: Coord, b: Coord) -> bool {
+        a != b && (a.0 == b.0 || a.1 == b.1 || (a.0 / 3 == b.0 / 3 && a.1 / 3 == b.1 / 3))
+    }
+
+    /// The number of filled cells. The game doesn't keep track of which cells were given, so this
+    /// is only the puzzle's clue count before any cells have been filled in.
+    pub fn clue_count(&self) -> usize {
+        self.iter_cells()
+            .filter(|&(_, _, cell)| cell.is_some())
+            .count()
+    }
+
+    /// The fraction of the board that's filled in, from 0.0 for an empty board to 1.0 for a full
+    /// one.
+    pub fn completion(&self) -> f32 {
+        self.clue_count() as f32 / 81.0
+    }
+
+    /// The values the solver still considers possible for a cell, including anything ruled out by
+    /// logical techniques. For a filled cell, that's just its value.
+    pub fn candidates(&self, (row, col): Coord) -> Candidates {
+        Candidates::from_bools(&self.cell_poss[row][col])
+    }
+
+    /// How many candidates each empty cell has, indexed as `[row][col]`, with 0 for filled cells.
+    pub fn candidate_counts(&self) -> [[usize; 9]; 9] {
+        let mut counts = [[0; 9]; 9];
+        for (y, x, cell, poss) in self.iter() {
+            if cell.is_none() {
+                counts[y][x] = poss.iter().filter(|&&p| p).count();
+            }
+        }
+        counts
+    }
+
+    /// Every empty cell with its candidates, in board order, for showing all the options at once.
+    /// Each list is allocated at exactly its size, so this does one allocation per empty cell and
+    /// one for the whole list.
+    pub fn all_valid_moves(&self) -> Vec<(usize, usize, Vec<CellValue>)> {
+        let counts = self.candidate_counts();
+        let empty = self
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_none())
+            .count();
+        let mut moves = Vec::with_capacity(empty);
+        for (y, x, cell, poss) in self.iter() {
+            if cell.is_none() {
+                let mut values = Vec::with_capacity(counts[y][x]);
+                values.extend((0..9).filter(|&i| poss[i]).map(CellValue::from_index));
+                moves.push((y, x, values));
+            }
+        }
+        moves
+    }
+
+    /// The values that have been ruled out for an empty cell, for showing crossed out pencil
+    /// marks. This is the complement of `candidates`, except that a filled cell has none.
+    pub fn impossibilities(&self, row: usize, col: usize) -> Vec<CellValue> {
+        if self.board[row][col].is_some() {
+            return Vec::new();
+        }
+        self.cell_poss[row][col]
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| !p)
+            .map(|(i, _)| CellValue::from_index(i))
+            .collect()
+    }
+
+    /// Checks whether a value could be placed in an empty cell without repeating a value in its
+    /// row, column, or 3x3. Unlike `candidates`, this only looks at the filled cells and not at
+    /// anything the solver has deduced, so a player interface can use it to reject illegal moves
+    /// without giving away deductions the player hasn't made.
+    pub fn can_place(&self, (row, col): Coord, cv: CellValue) -> Result<(), PlaceError> {
+        if row >= 9 || col >= 9 {
+            return Err(PlaceError::OutOfBounds);
+        }
+        if self.board[row][col].is_some() {
+            return Err(PlaceError::Filled);
+        }
+        match peers(row, col).find(|&(y, x)| self.board[y][x] == Some(cv)) {
+            Some((y, x)) => Err(PlaceError::Conflict { row: y, col: x }),
+            None => Ok(()),
+        }
+    }
+
+    /// The values that `can_place` would accept for a cell. This is deliberately weaker than
+    /// `candidates`; see `can_place`.
+    pub fn legal_values(&self, coord: Coord) -> Candidates {
+        let mut legal = Candidates::empty();
+        for cv in Candidates::all().iter() {
+            if self.can_place(coord, cv).is_ok() {
+                legal.insert(cv);
+            }
+        }
+        legal
+    }
+
+    /// Fills in a cell for a player, if `can_place` allows it.
+    pub fn place(&mut self, (row, col): Coord, cv: CellValue) -> Result<(), PlaceError> {
+        self.can_place((row, col), cv)?;
+        self.set_cell(row, col, cv);
+        Ok(())
+    }
+
+    /// Empties a cell. Candidates the player crossed off in it before it was filled stay crossed
+    /// off.
+    pub fn erase(&mut self, (row, col): Coord) -> Result<(), PlaceError> {
+        if row >= 9 || col >= 9 {
+            return Err(PlaceError::OutOfBounds);
+        }
+        self.unset_cell(row, col);
+        Ok(())
+    }
+
+    /// Whether a cell is one of the givens the game was made with, rather than filled in by a
+    /// player or the solver. Erasing a given makes it an ordinary empty cell.
+    pub fn is_given(&self, (row, col): Coord) -> bool {
+        row < 9 && col < 9 && self.givens[row][col]
+    }
+
+    /// Fills in one cell with its value from the solution, for a hint that gives the answer
+    /// outright. The solution is found from the givens alone, so nothing the player has filled in
+    /// sways it, and it has to be unique. A wrong value already in the cell is replaced, and any
+    /// cell the player filled in with the revealed value that sees this one is emptied, both of
+    /// which show up in the `Reveal`. The solution isn't kept between calls, since a `Game` is
+    /// copied at every step of a search and has to stay small. `PuzzleEditor::reveal` keeps it
+    /// until the clues change, for revealing many cells of one puzzle.
+    pub fn reveal(&mut self, coord: Coord) -> Result<Reveal, RevealError> {
+        self.check_revealable(coord)?;
+        let solution = self.givens_solution()?;
+        Ok(self.reveal_from(coord, &solution))
+    }
+
+    // Why a cell can't be revealed, if it can't, before looking at the solution.
+    fn check_revealable(&self, (row, col): Coord) -> Result<(), RevealError> {
+        if row >= 9 || col >= 9 {
+            Err(RevealError::OutOfBounds)
+        } else if self.givens[row][col] {
+            Err(RevealError::Given)
+        } else {
+            Ok(())
+        }
+    }
+
+    // The one solution of the givens, leaving out whatever else is filled in.
+    fn givens_solution(&self) -> Result<[[u8; 9]; 9], RevealError> {
+        let mut givens = [[0; 9]; 9];
+        for (y, x, &cell) in self.iter_cells() {
+            if self.givens[y][x] {
+                givens[y][x] = cell.map_or(0, |cv| cv as u8);
+            }
+        }
+        let mut solutions = Game::new(givens).solutions();
+        match (solutions.next(), solutions.next()) {
+            (None, _) => Err(RevealError::Unsolvable),
+            (Some(_), Some(_)) => Err(RevealError::NotUnique),
+            (Some(solution), None) => Ok(solution.to_array()),
+        }
+    }
+
+    // Fills in a cell that `check_revealable` allows with its value from the solution.
+    fn reveal_from(&mut self, (row, col): Coord, solution: &[[u8; 9]; 9]) -> Reveal {
+        let value = CellValue::new(solution[row][col]).expect("solutions are full boards");
+        let replaced = self.board[row][col].filter(|&cv| cv != value);
+        if replaced.is_some() {
+            self.unset_cell(row, col);
+        }
+        let cleared = peers(row, col)
+            .filter(|&(y, x)| self.board[y][x] == Some(value) && !self.givens[y][x])
+            .collect::<Vec<_>>();
+        for &(y, x) in &cleared {
+            self.unset_cell(y, x);
+        }
+        self.set_cell(row, col, value);
+        Reveal {
+            value,
+            replaced,
+            cleared,
+        }
+    }
+
+    /// Returns a copy of the game with one clue taken out, for testing many slightly different
+    /// puzzles in a row. Rather than rebuilding the whole board the way `Game::new` would, only
+    /// the cell and its peers get their candidates widened. For a game straight from `Game::new`,
+    /// the result is identical to calling `Game::new` on the smaller board, and `count_solutions`
+    /// and the other solvers start from it just the same.
+    pub fn without_clue(&self, (row, col): Coord) -> Result<Game, EraseError> {
+        if row >= 9 || col >= 9 {
+            return Err(EraseError::OutOfBounds);
+        }
+        if self.board[row][col].is_none() {
+            return Err(EraseError::Empty);
+        }
+        let mut game = *self;
+        game.unset_cell(row, col);
+        Ok(game)
+    }
+
+    /// Crosses off a candidate in an empty cell for a player. The solver treats this as a hard
+    /// constraint, so crossing off the value that belongs there leaves the game unsolvable.
+    pub fn eliminate_candidate(
+        &mut self,
+        (row, col): Coord,
+        cv: CellValue,
+    ) -> Result<(), ElimError> {
+        if row >= 9 || col >= 9 {
+            return Err(ElimError::OutOfBounds);
+        }
+        if self.board[row][col].is_some() {
+            return Err(ElimError::Filled);
+        }
+        self.crossed_off[row][col].insert(cv);
+        self.set_poss(row, col, usize::from(cv), false);
+        Ok(())
+    }
+
+    /// Undoes `eliminate_candidate`. The value only becomes a candidate again if nothing else
+    /// rules it out.
+    pub fn restore_candidate(&mut self, (row, col): Coord, cv: CellValue) -> Result<(), ElimError> {
+        if row >= 9 || col >= 9 {
+            return Err(ElimError::OutOfBounds);
+        }
+        if self.board[row][col].is_some() {
+            return Err(ElimError::Filled);
+        }
+        if !self.crossed_off[row][col].contains(cv) {
+            return Err(ElimError::NotCrossedOff);
+        }
+        self.crossed_off[row][col].remove(cv);
+        self.update_poss_from_flags(row, col);
+        Ok(())
+    }
+
+    /// Shows what placing a value in a cell would do without changing the game: which filled
+    /// cells it would repeat a value with, and which empty cells would be left with only one
+    /// possible value as a result.
+    pub fn preview(&self, row: usize, col: usize, cv: CellValue) -> Preview {
+        let conflicts = peers(row, col)
+            .filter(|&(y, x)| self.board[y][x] == Some(cv))
+            .collect();
+        let mut after = *self;
+        after.unset_cell(row, col);
+        after.set_cell(row, col, cv);
+        let newly_forced = after
+            .iter()
+            .filter(|&(y, x, cell, poss)| {
+                cell.is_none()
+                    && poss.iter().filter(|&&p| p).count() == 1
+                    && self.cell_poss[y][x].iter().filter(|&&p| p).count() > 1
+            })
+            .map(|(y, x, _, poss)| {
+                let i = poss.iter().position(|&p| p).unwrap();
+                (y, x, CellValue::from_index(i))
+            })
+            .collect();
+        Preview {
+            conflicts,
+            newly_forced,
+        }
     }
 
     fn iter(&self) -> impl Iterator<Item = (usize, usize, &Option<CellValue>, &[bool; 9])> + '_ {
@@ -143,94 +908,272 @@ impl Game {
         if self.board[row][col] == Some(cv) {
             return;
         }
+        debug_assert!(
+            self.board[row][col].is_none(),
+            "set_cell({}, {}) over a filled cell",
+            row,
+            col
+        );
         self.board[row][col] = Some(cv);
-        let mut set = [false; 9];
-        set[usize::from(cv)] = true;
-        self.cell_poss[row][col] = set;
-        self.cols_flags[col][usize::from(cv)] = true;
-        self.rows_flags[row][usize::from(cv)] = true;
+        // A filled cell isn't a place for any value, so it drops out of `positions` entirely.
+        let mut poss = [false; 9];
+        poss[usize::from(cv)] = true;
+        self.cell_poss[row][col] = poss;
+        for &(unit, j) in &Game::unit_slots(row, col) {
+            for mask in &mut self.positions[unit] {
+                *mask &= !(1 << j);
+            }
+        }
+        self.cols_flags[col][cv] = true;
+        self.rows_flags[row][cv] = true;
         let s = self.sqrs_ind(row, col);
-        self.sqrs_flags[s][usize::from(cv)] = true;
-        self.update_poss_from_flags(row, col);
+        self.sqrs_flags[s][cv] = true;
+        self.remove_from_peers(row, col, cv);
     }
 
     fn unset_cell(&mut self, row: usize, col: usize) {
-        let i = match self.board[row][col] {
-            Some(cv) => usize::from(cv),
+        let cv = match self.board[row][col] {
+            Some(cv) => cv,
             None => return,
         };
         self.board[row][col] = None;
-        self.cols_flags[col][i] = false;
-        self.rows_flags[row][i] = false;
+        self.givens[row][col] = false;
+        // The cell's one remaining candidate wasn't counted in `positions` while it was filled.
+        self.set_poss(row, col, usize::from(cv), true);
+        self.cols_flags[col][cv] = false;
+        self.rows_flags[row][cv] = false;
         let s = self.sqrs_ind(row, col);
-        self.sqrs_flags[s][i] = false;
-        self.update_poss_from_flags(row, col);
+        self.sqrs_flags[s][cv] = false;
+        // Values crossed off by logical techniques may have depended on this cell's value, so they
+        // can't be trusted any more.
+        if self.elims.iter().flatten().any(|elims| !elims.is_empty()) {
+            self.elims = [[Candidates::empty(); 9]; 9];
+            self.reset_poss();
+        } else {
+            self.update_poss_from_flags(row, col);
+        }
+        debug_assert!(
+            self.poss_match_board(),
+            "candidates out of step with the board after unset_cell({}, {})",
+            row,
+            col
+        );
     }
 
-    fn update_poss_from_flags(&mut self, row: usize, col: usize) {
-        // Set the new possibilities for the affected row
-        for (x, c) in self.cols_flags.iter().enumerate() {
-            if self.board[row][x].is_none() {
-                let s = 3 * (row / 3) + x / 3;
-                for (i, &c) in c.iter().enumerate() {
-                    let new = !(self.rows_flags[row][i] || c || self.sqrs_flags[s][i]);
-                    self.cell_poss[row][x][i] = new;
-                }
+    // Works out every cell's possible values again from the board, flags, and crossed off values,
+    // and checks they agree with the stored ones: a filled cell allows only its own value, and an
+    // empty one anything its units and crossings off don't rule out.
+    fn poss_match_board(&self) -> bool {
+        self.iter().all(|(y, x, cell, poss)| {
+            let s = self.sqrs_ind(y, x);
+            CellValue::ALL.iter().enumerate().all(|(i, &value)| {
+                let expected = match cell {
+                    Some(cv) => value == *cv,
+                    None => {
+                        !(self.rows_flags[y][value]
+                            || self.cols_flags[x][value]
+                            || self.sqrs_flags[s][value]
+                            || self.elims[y][x].contains(value)
+                            || self.crossed_off[y][x].contains(value))
+                    }
+                };
+                poss[i] == expected
+            })
+        })
+    }
+
+    // Recounts the row, column, and 3x3 flags from the board and checks they agree with the
+    // stored ones.
+    fn flags_match_board(&self) -> bool {
+        let mut rows_flags = [DigitArray([false; 9]); 9];
+        let mut cols_flags = [DigitArray([false; 9]); 9];
+        let mut sqrs_flags = [DigitArray([false; 9]); 9];
+        for (y, x, cell) in self.iter_cells() {
+            if let Some(cv) = *cell {
+                rows_flags[y][cv] = true;
+                cols_flags[x][cv] = true;
+                sqrs_flags[self.sqrs_ind(y, x)][cv] = true;
             }
         }
-        // Set the new possibilities for the affected column (less the removed cell, which was fixed
-        // by the previous loop)
-        for (y, r) in self
-            .rows_flags
-            .iter()
-            .enumerate()
-            .filter(|&(y, _)| y != row)
-        {
-            if self.board[y][col].is_none() {
-                let s = 3 * (y / 3) + col / 3;
-                for (i, &r) in r.iter().enumerate() {
-                    let new = !(r || self.cols_flags[col][i] || self.sqrs_flags[s][i]);
-                    self.cell_poss[y][col][i] = new;
-                }
+        rows_flags == self.rows_flags
+            && cols_flags == self.cols_flags
+            && sqrs_flags == self.sqrs_flags
+    }
+
+    fn eliminate(&mut self, row: usize, col: usize, cv: CellValue) {
+        self.elims[row][col].insert(cv);
+        self.set_poss(row, col, usize::from(cv), false);
+    }
+
+    // Sets whether a value is possible for a cell, keeping `positions` in step.
+    fn set_poss(&mut self, row: usize, col: usize, i: usize, possible: bool) {
+        self.cell_poss[row][col][i] = possible;
+        let counted = possible && self.board[row][col].is_none();
+        for &(unit, j) in &Game::unit_slots(row, col) {
+            if counted {
+                self.positions[unit][i] |= 1 << j;
+            } else {
+                self.positions[unit][i] &= !(1 << j);
             }
         }
-        // There should be four more cells in the 3x3 group not fixed by the previous two loops.
-        // Iterate over them and fix their possibilities.
+    }
+
+    // The row, column, and 3x3 a cell is in, as indices into `positions`, each with the cell's
+    // place in that unit.
+    fn unit_slots(row: usize, col: usize) -> [(usize, usize); 3] {
+        [
+            (row, col),
+            (9 + col, row),
+            (18 + 3 * (row / 3) + col / 3, 3 * (row % 3) + col % 3),
+        ]
+    }
+
+    // Rebuilds `positions` from scratch, for after `cell_poss` or the board was changed wholesale.
+    fn sync_positions(&mut self) {
+        self.positions = [[0; 9]; 27];
+        for (y, x) in (0..81).map(Game::coords) {
+            for i in 0..9 {
+                self.set_poss(y, x, i, self.cell_poss[y][x][i]);
+            }
+        }
+    }
+
+    // Works out an empty cell's possible values again from the flags and eliminations.
+    fn recompute_poss(&mut self, y: usize, x: usize) {
+        if self.board[y][x].is_some() {
+            return;
+        }
+        let s = self.sqrs_ind(y, x);
+        for (i, &cv) in CellValue::ALL.iter().enumerate() {
+            let new = !(self.rows_flags[y][cv]
+                || self.cols_flags[x][cv]
+                || self.sqrs_flags[s][cv]
+                || self.elims[y][x].contains(cv)
+                || self.crossed_off[y][x].contains(cv));
+            if new != self.cell_poss[y][x][i] {
+                self.set_poss(y, x, i, new);
+            }
+        }
+    }
+
+    fn reset_poss(&mut self) {
+        for (y, x) in (0..81).map(Game::coords) {
+            self.recompute_poss(y, x);
+        }
+    }
+
+    // Takes a newly filled in cell's value away from the empty cells that see it. That's the only
+    // candidate filling in a cell can rule out, so there's no need to work out the rest again.
+    fn remove_from_peers(&mut self, row: usize, col: usize, cv: CellValue) {
+        let i = usize::from(cv);
         let rs = 3 * (row / 3);
         let cs = 3 * (col / 3);
-        for y in (rs..rs + 3).filter(|&y| y != row) {
-            for x in (cs..cs + 3).filter(|&x| x != col) {
-                if self.board[y][x].is_none() {
-                    let s = 3 * (y / 3) + x / 3;
-                    for i in 0..9 {
-                        let new = !(self.rows_flags[y][i]
-                            || self.cols_flags[x][i]
-                            || self.sqrs_flags[s][i]);
-                        self.cell_poss[y][x][i] = new;
-                    }
-                }
+        let row_cells = (0..9).filter(|&x| x != col).map(|x| (row, x));
+        let col_cells = (0..9).filter(|&y| y != row).map(|y| (y, col));
+        let sqr_cells = (rs..rs + 3)
+            .filter(|&y| y != row)
+            .flat_map(|y| (cs..cs + 3).filter(|&x| x != col).map(move |x| (y, x)));
+        for (y, x) in row_cells.chain(col_cells).chain(sqr_cells) {
+            if self.board[y][x].is_none() && self.cell_poss[y][x][i] {
+                self.set_poss(y, x, i, false);
             }
         }
     }
 
-    pub fn propagate_poss_to_board(&mut self) -> bool {
-        // Only try to make changes if the game isn't already solved
-        if !self.solved() {
+    fn update_poss_from_flags(&mut self, row: usize, col: usize) {
+        // The affected row, then the affected column less the cell itself, which was fixed with
+        // the row
+        for x in 0..9 {
+            self.recompute_poss(row, x);
+        }
+        for y in (0..9).filter(|&y| y != row) {
+            self.recompute_poss(y, col);
+        }
+        // There should be four more cells in the 3x3 group not fixed by the previous two loops.
+        let rs = 3 * (row / 3);
+        let cs = 3 * (col / 3);
+        for y in (rs..rs + 3).filter(|&y| y != row) {
+            for x in (cs..cs + 3).filter(|&x| x != col) {
+                self.recompute_poss(y, x);
+            }
+        }
+    }
+
+    /// Makes a single propagation sweep, the way `propagate` does repeatedly until nothing
+    /// changes, and returns the cells it filled in. This is for stepping through propagation one
+    /// sweep at a time, say to animate it.
+    pub fn propagate_once(&mut self) -> Vec<(usize, usize, CellValue)> {
+        let before = self.board;
+        self.propagate_poss_to_board();
+        self.iter_cells()
+            .filter(|&(y, x, _)| before[y][x].is_none())
+            .filter_map(|(y, x, &cell)| cell.map(|cv| (y, x, cv)))
+            .collect()
+    }
+
+    /// Fills in every cell that's the only place left for a value in some row, column, or 3x3, or
+    /// that has only one possible value left, over and over until nothing changes. This is the
+    /// part of `solve` that doesn't guess. Returns what changed.
+    pub fn propagate(&mut self) -> PropagationReport {
+        let before = *self;
+        let passes = self.propagate_to_fixpoint();
+        let mut report = PropagationReport {
+            placements: Vec::new(),
+            eliminations: Vec::new(),
+            passes,
+            solved: self.solved(),
+        };
+        for (y, x, cell, poss) in self.iter() {
+            if let (None, Some(cv)) = (before.board[y][x], cell) {
+                report.placements.push((y, x, *cv));
+            } else if cell.is_none() {
+                report.eliminations.extend(
+                    (0..9)
+                        .filter(|&i| before.cell_poss[y][x][i] && !poss[i])
+                        .map(|i| (y, x, CellValue::from_index(i))),
+                );
+            }
+        }
+        report
+    }
+
+    // Sweeps until nothing changes, and returns how many sweeps changed something. Everything
+    // that propagates, `propagate` and the solvers alike, goes through here.
+    fn propagate_to_fixpoint(&mut self) -> usize {
+        let mut passes = 0;
+        while self.propagate_poss_to_board() {
+            passes += 1;
+        }
+        passes
+    }
+
+    // One sweep of propagation. Returns whether it filled in anything.
+    fn propagate_poss_to_board(&mut self) -> bool {
+        // Only try to make changes if the game isn't already solved
+        if !self.solved() {
             let mut made_change = false;
-            for cv in 0..9 {
+            // The searches for the last place a value can go only look at empty cells. A filled
+            // cell's possibilities still hold its own value, and setting it again would leave the
+            // flags out of step with the board.
+            for &cv in &CellValue::ALL {
+                let i = usize::from(cv);
                 if self.rows_flags.iter().filter(|b| b[cv]).count() == 8 {
                     let r = self.rows_flags.iter().position(|b| !b[cv]).expect("rfr");
-                    let c = self.iter_row_poss(r).position(|(_, cell)| cell[cv]);
+                    let c = self
+                        .iter_row_poss(r)
+                        .position(|(x, cell)| self.board[r][x].is_none() && cell[i]);
                     if let Some(c) = c {
-                        self.set_cell(r, c, CellValue::new(cv as u8 + 1).expect("rfcv"));
+                        self.set_cell(r, c, cv);
                         made_change = true;
                     }
                 }
                 if self.cols_flags.iter().filter(|b| b[cv]).count() == 8 {
                     let c = self.cols_flags.iter().position(|b| !b[cv]).expect("cfc");
-                    let r = self.iter_col_poss(c).position(|(_, cell)| cell[cv]);
+                    let r = self
+                        .iter_col_poss(c)
+                        .position(|(y, cell)| self.board[y][c].is_none() && cell[i]);
                     if let Some(r) = r {
-                        self.set_cell(r, c, CellValue::new(cv as u8 + 1).expect("cfcv"));
+                        self.set_cell(r, c, cv);
                         made_change = true;
                     }
                 }
@@ -240,13 +1183,13 @@ impl Game {
                     let cs = 3 * (s % 3);
                     let p = self
                         .iter_3x3_poss(rs, cs)
-                        .position(|(_, _, cell)| cell[cv]);
+                        .position(|(y, x, cell)| self.board[y][x].is_none() && cell[i]);
                     if let Some(p) = p {
                         let ro = p / 3;
                         let co = p % 3;
                         let r = rs + ro;
                         let c = cs + co;
-                        self.set_cell(r, c, CellValue::new(cv as u8 + 1).expect("sfcv"));
+                        self.set_cell(r, c, cv);
                         made_change = true;
                     }
                 }
@@ -254,12 +1197,12 @@ impl Game {
             for y in 0..9 {
                 for x in 0..9 {
                     // Only check possibilities if the board has no value in a cell
-                    if self.board[y][x].is_none() {
-                        if self.cell_poss[y][x].iter().copied().filter(|&b| b).count() == 1 {
-                            let cv = self.cell_poss[y][x].iter().position(|&b| b).unwrap();
-                            self.set_cell(y, x, CellValue::new(cv as u8 + 1).expect("xcv"));
-                            made_change = true;
-                        }
+                    if self.board[y][x].is_none()
+                        && self.cell_poss[y][x].iter().copied().filter(|&b| b).count() == 1
+                    {
+                        let i = self.cell_poss[y][x].iter().position(|&b| b).unwrap();
+                        self.set_cell(y, x, CellValue::from_index(i));
+                        made_change = true;
                     }
                 }
             }
@@ -269,21 +1212,65 @@ impl Game {
         }
     }
 
-    pub fn solve(&mut self) {
-        if self.solved() {
-            return;
-        }
-        // Solve as much of the puzzle as is possible without any sort of foresight - just cancel
-        // out possible values and put in values for cells with only one possible value for as long
-        // as possible.
-        loop {
-            if !self.propagate_poss_to_board() {
-                break;
-            }
+    /// Fills in the board with a solution. For a game with more than one solution, this is the
+    /// first one in the order of `solutions`, as long as the config doesn't set `break_symmetry`
+    /// or `any_order`.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        self.solve_with_config(&SolverConfig::default())
+    }
+
+    /// Solves into `out`, leaving this game as it is. `out` is overwritten with a copy of this
+    /// game first, so whatever it held before doesn't matter. This lets a game that's shared
+    /// read-only, say between the threads of a server, be solved by each without locking it for
+    /// writing.
+    pub fn solve_into(&self, out: &mut Game) -> Result<(), SolveError> {
+        *out = *self;
+        out.solve()
+    }
+
+    pub fn solve_with_config(&mut self, config: &SolverConfig) -> Result<(), SolveError> {
+        self.solve_hooked(&SearchHooks {
+            config: *config,
+            ..SearchHooks::default()
+        })
+    }
+
+    /// Like `solve`, but calls `callback` with every state the search reaches once propagation
+    /// has done all it can there, along with how many guesses deep the search is, starting from 0
+    /// before any guess has been made.
+    pub fn solve_with_callback(
+        &mut self,
+        callback: impl FnMut(&Game, usize),
+    ) -> Result<(), SolveError> {
+        let callback = RefCell::new(callback);
+        self.solve_hooked(&SearchHooks {
+            callback: Some(&callback),
+            ..SearchHooks::default()
+        })
+    }
+
+    /// Fills in the board with any valid completion, for setting up test boards. It makes no
+    /// difference how many completions there are: this is always the first one in the order of
+    /// `solutions`, so the empty board always gets the same grid. Returns whether there was a
+    /// completion to fill in. This says nothing about whether a puzzle is proper; use
+    /// `is_well_formed` for that.
+    pub fn any_completion(&mut self) -> bool {
+        self.solve().is_ok()
+    }
+
+    /// Solves the game and returns the filled in board as plain numbers, or `None` if there's no
+    /// solution.
+    pub fn solved_array(mut self) -> Option<[[u8; 9]; 9]> {
+        self.solve().ok().map(|()| self.to_array())
+    }
+
+    pub(crate) fn solve_hooked(&mut self, hooks: &SearchHooks) -> Result<(), SolveError> {
+        // However the board came to be, a contradiction in it can't be searched past.
+        if !self.is_valid(false) {
+            return Err(SolveError::Invalid);
         }
-        // If this solves the puzzle, hooray! Easy win, just return.
         if self.solved() {
-            return;
+            return Ok(());
         }
         // Each level of recursion represents a single move. So the maximum level of recursion is
         // the number of moves left to make. It shouldn't be possible to go over this cap, but this
@@ -294,96 +1281,426 @@ impl Game {
                 .iter()
                 .map(|row| row.iter().filter(|cv| cv.is_some()).count())
                 .sum::<usize>();
-        // Get the coordinates and possibilities for the first cell with more than one possible
-        // value.
-        let (y, x, poss) = self
-            .iter()
-            .find(|&(_, _, cell, _)| cell.is_none())
-            .map(|(y, x, _, &poss)| (y, x, poss))
-            .unwrap();
-        // Iterate over the possible values the cell can be.
-        for cv in poss
-            .iter()
-            .enumerate()
-            .filter(|&(_, &p)| p)
-            .map(|(i, _)| CellValue::new(i as u8 + 1).unwrap())
-        {
-            let mut new = *self;
-            // Set the cell to the possible value
-            new.set_cell(y, x, cv);
-            // Make sure that this change is valid (especially that it leaves possibilities).
-            if !new.is_valid(false) {
-                new.unset_cell(y, x);
-                continue;
-            }
-            // If that move solved the game, return.
-            if new.solved() {
-                *self = new;
-                return;
-            }
-            // If it didn't, this becomes the base of a recursive walk over the possible moves for
-            // the game with that as the starting point. If this tree produces a solved game (the
-            // recursive call returns `true`), then return. Otherwise, undo the move and try the
-            // next one.
-            if new.solve_recursive(1, depth_cap) {
-                *self = new;
-                return;
-            }
+        let givens = self.board;
+        if self.solve_recursive(0, depth_cap, hooks)? {
+            debug_assert!(
+                givens
+                    .iter()
+                    .flatten()
+                    .zip(self.board.iter().flatten())
+                    .all(|(given, cell)| given.is_none() || given == cell),
+                "solving changed a given cell"
+            );
+            Ok(())
+        } else {
+            Err(SolveError::Unsolvable)
         }
-        panic!("Found no unique solution to game.");
     }
 
-    fn solve_recursive(&mut self, depth: usize, max_depth: usize) -> bool {
+    fn solve_recursive(
+        &mut self,
+        depth: usize,
+        max_depth: usize,
+        hooks: &SearchHooks,
+    ) -> Result<bool, SolveError> {
         if depth > max_depth {
-            return false;
+            return Ok(false);
         }
+        hooks.visit()?;
         // Solve as much of the puzzle as is possible without any sort of foresight - just cancel
         // out possible values and put in values for cells with only one possible value for as long
         // as possible.
-        loop {
-            if !self.propagate_poss_to_board() {
-                break;
-            }
+        self.propagate_to_fixpoint();
+        if let Some(callback) = hooks.callback {
+            (callback.borrow_mut())(self, depth);
+        }
+        // Propagation can walk into a contradiction, and there's no use searching below a state
+        // where some row, column, or 3x3 can no longer be completed.
+        if !self.is_valid(false) || !self.units_completable() {
+            return Ok(false);
         }
         // If this solves the puzzle, hooray! Easy win, just return.
         if self.solved() {
-            return true;
+            return Ok(true);
         }
-        // Get the coordinates and possibilities for the first cell with more than one possible
-        // value. The `unwrap` is safe here since this method never gets called if there are no
-        // empty cells left.
-        let (y, x, poss) = self
-            .iter()
-            .find(|&(_, _, cell, _)| cell.is_none())
-            .map(|(y, x, _, &poss)| (y, x, poss))
-            .unwrap();
+        // Get the coordinates and possibilities for a cell with more than one possible value. If
+        // there isn't one, propagation filled the board in wrong.
+        let (y, x, poss) = match self.branch_cell(&hooks.config) {
+            Some(cell) => cell,
+            None => return Ok(false),
+        };
         // Iterate over the possible values the cell can be and branch to all the possible moves
         // after this one. If a move solves the game or if a branch returns true, return `true`
         // immediately to walk back up the stack to the base of the tree and return. If a branch
         // returns false, try the next one. If all branches are exhausted and no solution has been
         // found, then this is a bad branch so return `false`.
-        for cv in poss
-            .iter()
-            .enumerate()
-            .filter(|&(_, &p)| p)
-            .map(|(i, _)| CellValue::new(i as u8 + 1).unwrap())
-        {
+        for cv in self.branch_values(&poss, &hooks.config) {
             let mut new = *self;
             new.set_cell(y, x, cv);
+            // Make sure that this change is valid (especially that it leaves possibilities).
             if !new.is_valid(false) {
-                new.unset_cell(y, x);
                 continue;
             }
-            if new.solved() {
-                return true;
-            }
-            if new.solve_recursive(depth + 1, max_depth) {
+            if new.solved() || new.solve_recursive(depth + 1, max_depth, hooks)? {
                 *self = new;
-                return true;
-            } else {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Like `solve`, but searches breadth-first, so that the solution filled in is one that takes
+    /// the fewest guesses to reach, branching on cells the same way `solve` does. Returns how many
+    /// guesses that was. For a game with several solutions, this picks the one closest to the
+    /// givens rather than the first in order.
+    ///
+    /// Every game at the depth being searched is kept in memory at once, each a few kilobytes, and
+    /// there can be many on sparse boards. The search fails with `SolveError::FrontierFull`
+    /// rather than hold more than `max_frontier` of them.
+    pub fn solve_bfs(&mut self, max_frontier: usize) -> Result<usize, SolveError> {
+        if !self.is_valid(false) {
+            return Err(SolveError::Invalid);
+        }
+        let config = SolverConfig::default();
+        let mut frontier = VecDeque::new();
+        frontier.push_back((*self, 0));
+        // Games come out in the order they went in, so every game at one depth is looked at
+        // before any at the next.
+        while let Some((mut game, guesses)) = frontier.pop_front() {
+            game.propagate_to_fixpoint();
+            if !game.is_valid(false) || !game.units_completable() {
+                continue;
+            }
+            let (y, x, poss) = match game.branch_cell(&config) {
+                Some(cell) => cell,
+                None => {
+                    *self = game;
+                    return Ok(guesses);
+                }
+            };
+            for cv in game.branch_values(&poss, &config) {
+                if frontier.len() >= max_frontier {
+                    return Err(SolveError::FrontierFull);
+                }
+                let mut new = game;
+                new.set_cell(y, x, cv);
+                frontier.push_back((new, guesses + 1));
+            }
+        }
+        Err(SolveError::Unsolvable)
+    }
+
+    /// Runs the same search as `solve` on a copy of the game, but records every branch it takes,
+    /// dead ends included, for visualizing or debugging the search. The search stops once
+    /// `max_nodes` branches have been tried, marking any node it cut short as truncated.
+    pub fn solve_tree(&self, max_nodes: usize) -> SearchNode {
+        let mut root = SearchNode::default();
+        let mut game = *self;
+        if game.is_valid(false) {
+            let mut budget = max_nodes;
+            game.tree_recursive(&mut root, &mut budget);
+        }
+        root
+    }
+
+    fn tree_recursive(&mut self, node: &mut SearchNode, budget: &mut usize) {
+        self.propagate_to_fixpoint();
+        if self.solved() {
+            node.solved = true;
+            return;
+        }
+        let (y, x, poss) = match self.branch_cell(&SolverConfig::default()) {
+            Some(cell) => cell,
+            None => return,
+        };
+        for cv in self.branch_values(&poss, &SolverConfig::default()) {
+            if *budget == 0 {
+                node.truncated = true;
+                return;
+            }
+            *budget -= 1;
+            let mut child = SearchNode {
+                cell: Some((y, x)),
+                value: Some(cv),
+                ..SearchNode::default()
+            };
+            let mut new = *self;
+            new.set_cell(y, x, cv);
+            if new.is_valid(false) {
+                new.tree_recursive(&mut child, budget);
+            }
+            let solved = child.solved;
+            node.children.push(child);
+            if solved {
+                node.solved = true;
+                return;
+            }
+        }
+    }
+
+    /// Counts the solutions to the game, stopping as soon as `cap` of them have been found. A
+    /// `cap` of 2 is enough to tell whether the game has a unique solution.
+    pub fn count_solutions(&self, cap: usize) -> usize {
+        self.count_solutions_with_config(cap, &SolverConfig::default())
+    }
+
+    pub fn count_solutions_with_config(&self, cap: usize, config: &SolverConfig) -> usize {
+        self.count_solutions_hooked(
+            cap,
+            &SearchHooks {
+                config: *config,
+                ..SearchHooks::default()
+            },
+        )
+    }
+
+    // Counts solutions the way `count_solutions_with_config` does, telling `hooks` about every
+    // node the counting search expands.
+    pub(crate) fn count_solutions_hooked(&self, cap: usize, hooks: &SearchHooks) -> usize {
+        let config = &hooks.config;
+        // With `k` values missing from the board, every solution comes with the `k!` others made
+        // by swapping those values around, so once one solution turns up there are at least that
+        // many. Finding the first is all the search there is to do when the cap is no higher.
+        // Symmetry breaking already counts each such group once, so it's left to the search. A
+        // value crossed off an empty cell, by a player or a restriction, only rules out one side
+        // of a swap, so then there's nothing to go on but the search.
+        let missing = 9 - self.distinct_given_digits();
+        let swaps = (1..=missing).product::<usize>();
+        if cap > 0
+            && missing >= 2
+            && cap <= swaps
+            && !config.break_symmetry
+            && !self.has_crossed_off()
+        {
+            let mut game = *self;
+            let any_order = SolverConfig {
+                any_order: true,
+                ..*config
+            };
+            return match game.solve_with_config(&any_order) {
+                Ok(()) => cap,
+                Err(_) => 0,
+            };
+        }
+        let mut count = 0;
+        if cap > 0 {
+            let mut game = *self;
+            game.count_recursive(cap, &mut count, hooks);
+        }
+        count
+    }
+
+    /// Every solution to the game, found lazily in order of their `to_line` strings. With
+    /// `SolverConfig::any_order` or `break_symmetry` set, the order isn't guaranteed.
+    pub fn solutions(&self) -> Solutions {
+        self.solutions_with_config(&SolverConfig::default())
+    }
+
+    pub fn solutions_with_config(&self, config: &SolverConfig) -> Solutions {
+        Solutions {
+            stack: vec![*self],
+            config: *config,
+        }
+    }
+
+    /// Estimates how many solutions the game has, for boards with far too many to count. Each
+    /// sample fills in the board with random choices, multiplying together the number of values
+    /// that could have been chosen at each step, and the estimate is the average of those
+    /// products. Samples that walk into a contradiction count as 0, which keeps the estimate
+    /// unbiased. Its variance is huge on sparse boards though, so it takes many samples to be
+    /// accurate to within a factor of a few, and a small number of samples tends to underestimate.
+    /// A `SplitMix64` with a fixed seed gives the same estimate every time.
+    pub fn estimate_solution_count(&self, samples: usize, rng: &mut impl SudokuRng) -> f64 {
+        if samples == 0 {
+            return 0.0;
+        }
+        let config = SolverConfig {
+            any_order: true,
+            ..SolverConfig::default()
+        };
+        let total = (0..samples)
+            .map(|_| {
+                let mut game = *self;
+                let mut weight = 1.0;
+                loop {
+                    game.propagate_to_fixpoint();
+                    if !game.is_valid(false) || !game.units_completable() {
+                        return 0.0;
+                    }
+                    let (y, x, poss) = match game.branch_cell(&config) {
+                        Some(cell) => cell,
+                        None => return weight,
+                    };
+                    let values = game.branch_values(&poss, &config);
+                    weight *= values.len() as f64;
+                    game.set_cell(y, x, values[rng.below(values.len())]);
+                }
+            })
+            .fold(0.0, |total, weight| total + weight);
+        total / samples as f64
+    }
+
+    /// Counts, up to `cap`, the ways the board can still be completed as it stands now, including
+    /// any cells a player has filled in. As correct cells go in this falls toward 1, and it drops
+    /// to 0 as soon as a wrong one does.
+    pub fn remaining_completions(&self, cap: usize) -> usize {
+        self.count_solutions(cap)
+    }
+
+    fn count_recursive(&mut self, cap: usize, count: &mut usize, hooks: &SearchHooks) {
+        if hooks.visit().is_err() {
+            return;
+        }
+        let config = &hooks.config;
+        self.propagate_to_fixpoint();
+        // Propagation can walk into a contradiction, and there's no use searching below a state
+        // where some row, column, or 3x3 can no longer be completed.
+        if !self.is_valid(false) || !self.units_completable() {
+            return;
+        }
+        // With no empty cells left and no conflicts, this is a solution.
+        if self.iter_cells().all(|(_, _, cell)| cell.is_some()) {
+            *count += 1;
+            return;
+        }
+        // Which solution gets found first doesn't matter here since they're only being counted,
+        // so let the search branch wherever keeps the tree narrowest.
+        let any_order = SolverConfig {
+            any_order: true,
+            ..*config
+        };
+        let (y, x, poss) = self.branch_cell(&any_order).unwrap();
+        for cv in self.branch_values(&poss, config) {
+            let mut new = *self;
+            new.set_cell(y, x, cv);
+            new.count_recursive(cap, count, hooks);
+            if *count >= cap {
+                return;
+            }
+        }
+    }
+
+    // The empty cell to branch on. Taking the first one in row-major order and trying its values
+    // smallest first means that every solution below one branch comes before every solution below
+    // the next, which is what keeps solutions in order. Without that guarantee, the cell with the
+    // fewest possible values keeps the tree narrower.
+    fn branch_cell(&self, config: &SolverConfig) -> Option<(usize, usize, [bool; 9])> {
+        let mut empty = self.iter().filter(|&(_, _, cell, _)| cell.is_none());
+        let cell = if config.any_order {
+            empty.min_by_key(|&(_, _, _, poss)| poss.iter().filter(|&&p| p).count())
+        } else {
+            empty.next()
+        };
+        cell.map(|(y, x, _, &poss)| (y, x, poss))
+    }
+
+    // The values to try in a cell when branching, smallest first.
+    fn branch_values(&self, poss: &[bool; 9], config: &SolverConfig) -> Vec<CellValue> {
+        let mut values = poss
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p)
+            .map(|(i, _)| CellValue::from_index(i))
+            .collect::<Vec<_>>();
+        // Values that aren't on the board anywhere are interchangeable, so trying the first of them
+        // covers the rest. Not once some are crossed off an empty cell though, since that cell
+        // tells them apart.
+        if config.break_symmetry && !self.has_crossed_off() {
+            let unused = |cv: &CellValue| !self.rows_flags.iter().any(|r| r[*cv]);
+            if let Some(first) = values.iter().copied().find(unused) {
+                values.retain(|cv| !unused(cv) || *cv == first);
             }
         }
-        false
+        values
+    }
+
+    // Whether any empty cell has values crossed off, by a player, a restriction, or a logical
+    // technique.
+    fn has_crossed_off(&self) -> bool {
+        self.iter_cells().any(|(y, x, cell)| {
+            cell.is_none() && !(self.crossed_off[y][x].is_empty() && self.elims[y][x].is_empty())
+        })
+    }
+
+    /// Checks that the game is a proper puzzle: it follows the rules and has exactly one solution.
+    pub fn is_well_formed(&self) -> bool {
+        self.is_valid(false) && self.count_solutions(2) == 1
+    }
+
+    /// Lists every filled cell whose value could be removed on its own without the game losing its
+    /// unique solution. Each clue is checked independently, so removing several of the listed
+    /// clues at once may still leave a game with multiple solutions. A game that doesn't already
+    /// have a unique solution has no redundant clues.
+    pub fn redundant_clues(&self) -> Vec<Coord> {
+        if self.count_solutions(2) != 1 {
+            return Vec::new();
+        }
+        self.iter_cells()
+            .filter_map(|(y, x, &cell)| cell.map(|cv| (y, x, cv)))
+            .filter(|&(y, x, cv)| self.clue_is_redundant(y, x, cv))
+            .map(|(y, x, _)| (y, x))
+            .collect()
+    }
+
+    /// Lists every filled cell that isn't in `redundant_clues`, i.e. every clue that the game
+    /// needs to keep its unique solution.
+    pub fn essential_clues(&self) -> Vec<Coord> {
+        let redundant = self.redundant_clues();
+        self.iter_cells()
+            .filter(|&(y, x, cell)| cell.is_some() && !redundant.contains(&(y, x)))
+            .map(|(y, x, _)| (y, x))
+            .collect()
+    }
+
+    fn clue_is_redundant(&self, row: usize, col: usize, cv: CellValue) -> bool {
+        // The game has a unique solution, and it has `cv` at this cell. So with the clue removed,
+        // the only way to gain a solution is with some other value here. Trying each of those
+        // with a capped count is much cheaper than counting up to two solutions from scratch.
+        let mut removed = *self;
+        removed.unset_cell(row, col);
+        let poss = removed.cell_poss[row][col];
+        poss.iter()
+            .enumerate()
+            .filter(|&(i, &p)| p && i != usize::from(cv))
+            .map(|(i, _)| CellValue::from_index(i))
+            .all(|other| {
+                let mut alt = removed;
+                alt.set_cell(row, col, other);
+                !alt.is_valid(false) || alt.count_solutions(1) == 0
+            })
+    }
+
+    /// Estimates how hard the game is to solve by propagating possibilities as far as they go
+    /// without any guessing and measuring what's left. This is only a heuristic: a game with a
+    /// large remaining search space will usually need more backtracking, but a few well-placed
+    /// guesses can still collapse it quickly. It never searches, so it's cheap enough to run over
+    /// large collections of games. On the rating fixtures and the boards in `gameboards`, the rank
+    /// correlation between `log_search_space` and the difficulty tier from `rate` is about 0.4:
+    /// harder games tend to leave more to search, but it's only good for sorting in bulk.
+    pub fn estimate_hardness(&self) -> HardnessEstimate {
+        let mut game = *self;
+        game.propagate_to_fixpoint();
+        let counts = game
+            .iter()
+            .filter(|&(_, _, cell, _)| cell.is_none())
+            .map(|(_, _, _, poss)| poss.iter().filter(|&&p| p).count())
+            .collect::<Vec<_>>();
+        HardnessEstimate {
+            empty_cells: counts.len(),
+            candidates: counts.iter().sum(),
+            log_search_space: counts.iter().fold(0.0, |acc, &c| acc + (c as f64).log10()),
+        }
+    }
+
+    fn units_completable(&self) -> bool {
+        // Every value missing from a row, column, or 3x3 needs at least one empty cell left in that
+        // group that can still take it. `positions` lists the units in that order.
+        self.rows_flags
+            .iter()
+            .chain(&self.cols_flags)
+            .chain(&self.sqrs_flags)
+            .zip(&self.positions)
+            .all(|(placed, places)| placed.iter().zip(places).all(|(&p, &mask)| p || mask != 0))
     }
 
     fn solved(&self) -> bool {
@@ -412,41 +1729,60 @@ impl Game {
     }
 
     fn is_valid(&self, verbose: bool) -> bool {
+        let mut report = String::new();
+        let valid = self.check_rules(if verbose { Some(&mut report) } else { None });
+        if verbose {
+            print!("{}", report);
+        }
+        valid
+    }
+
+    // Whether the board keeps to the rules, writing what's wrong with it to `report` if not.
+    fn check_rules(&self, report: Option<&mut String>) -> bool {
+        use std::fmt::Write;
         // Make sure there aren't any unset cells with no possible values
         if let Some((y, x, _, _)) = self
             .iter()
             .find(|&(_, _, cell, poss)| cell.is_none() && !poss.contains(&true))
         {
-            if verbose {
-                println!("Cell ({}, {}) has no possible values", x, y);
+            if let Some(report) = report {
+                let _ = writeln!(
+                    report,
+                    "Cell {} has no possible values",
+                    Game::cell_name(y, x)
+                );
             }
             false
         } else {
             // Otherwise, check to make sure there are no conflicts in rows, columns, or 3x3s.
-            let mut rows = [[false; 9]; 9];
-            let mut cols = [[false; 9]; 9];
-            let mut sqrs = [[false; 9]; 9];
+            let mut rows = [DigitArray([false; 9]); 9];
+            let mut cols = [DigitArray([false; 9]); 9];
+            let mut sqrs = [DigitArray([false; 9]); 9];
             for (y, x, &cell) in self.iter_cells() {
                 if let Some(cv) = cell {
-                    let i = usize::from(cv);
                     let s = 3 * (y / 3) + x / 3;
-                    if rows[y][i] || cols[x][i] || sqrs[s][i] {
-                        if verbose {
-                            if rows[y][i] {
-                                println!("Conflict: row {} has multiple {}s", y, i + 1);
+                    if rows[y][cv] || cols[x][cv] || sqrs[s][cv] {
+                        if let Some(report) = report {
+                            let n = cv as u8;
+                            if rows[y][cv] {
+                                let _ =
+                                    writeln!(report, "Conflict: row {} has multiple {}s", y + 1, n);
                             }
-                            if cols[x][i] {
-                                println!("Conflict: col {} has multiple {}s", x, i + 1);
+                            if cols[x][cv] {
+                                let _ =
+                                    writeln!(report, "Conflict: col {} has multiple {}s", x + 1, n);
                             }
-                            if sqrs[s][i] {
-                                println!("Conflict: sqr {} has multiple {}s", s, i + 1);
+                            if sqrs[s][cv] {
+                                let _ =
+                                    writeln!(report, "Conflict: 3x3 {} has multiple {}s", s + 1, n);
                             }
+                            let _ = writeln!(report, "Conflict found at {}", Game::cell_name(y, x));
                         }
                         return false;
                     } else {
-                        rows[y][i] = true;
-                        cols[x][i] = true;
-                        sqrs[s][i] = true;
+                        rows[y][cv] = true;
+                        cols[x][cv] = true;
+                        sqrs[s][cv] = true;
                     }
                 }
             }
@@ -457,153 +1793,572 @@ impl Game {
     fn sqrs_ind(&self, row: usize, col: usize) -> usize {
         3 * (row / 3) + col / 3
     }
-
-    fn cell_char(&self, row: usize, col: usize) -> char {
-        match self.board[row][col].map(|n| n as u8) {
-            Some(1) => '1',
-            Some(2) => '2',
-            Some(3) => '3',
-            Some(4) => '4',
-            Some(5) => '5',
-            Some(6) => '6',
-            Some(7) => '7',
-            Some(8) => '8',
-            Some(9) => '9',
-            None => ' ',
-            _ => unreachable!(),
-        }
-    }
 }
 
 impl fmt::Display for Game {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "┌───┬───┬───╥───┬───┬───╥───┬───┬───┐",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(0, 0),
-            self.cell_char(0, 1),
-            self.cell_char(0, 2),
-            self.cell_char(0, 3),
-            self.cell_char(0, 4),
-            self.cell_char(0, 5),
-            self.cell_char(0, 6),
-            self.cell_char(0, 7),
-            self.cell_char(0, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(1, 0),
-            self.cell_char(1, 1),
-            self.cell_char(1, 2),
-            self.cell_char(1, 3),
-            self.cell_char(1, 4),
-            self.cell_char(1, 5),
-            self.cell_char(1, 6),
-            self.cell_char(1, 7),
-            self.cell_char(1, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(2, 0),
-            self.cell_char(2, 1),
-            self.cell_char(2, 2),
-            self.cell_char(2, 3),
-            self.cell_char(2, 4),
-            self.cell_char(2, 5),
-            self.cell_char(2, 6),
-            self.cell_char(2, 7),
-            self.cell_char(2, 8)
-        )?;
-        writeln!(f, "╞═══╪═══╪═══╬═══╪═══╪═══╬═══╪═══╪═══╡",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(3, 0),
-            self.cell_char(3, 1),
-            self.cell_char(3, 2),
-            self.cell_char(3, 3),
-            self.cell_char(3, 4),
-            self.cell_char(3, 5),
-            self.cell_char(3, 6),
-            self.cell_char(3, 7),
-            self.cell_char(3, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(4, 0),
-            self.cell_char(4, 1),
-            self.cell_char(4, 2),
-            self.cell_char(4, 3),
-            self.cell_char(4, 4),
-            self.cell_char(4, 5),
-            self.cell_char(4, 6),
-            self.cell_char(4, 7),
-            self.cell_char(4, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(5, 0),
-            self.cell_char(5, 1),
-            self.cell_char(5, 2),
-            self.cell_char(5, 3),
-            self.cell_char(5, 4),
-            self.cell_char(5, 5),
-            self.cell_char(5, 6),
-            self.cell_char(5, 7),
-            self.cell_char(5, 8)
-        )?;
-        writeln!(f, "╞═══╪═══╪═══╬═══╪═══╪═══╬═══╪═══╪═══╡",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(6, 0),
-            self.cell_char(6, 1),
-            self.cell_char(6, 2),
-            self.cell_char(6, 3),
-            self.cell_char(6, 4),
-            self.cell_char(6, 5),
-            self.cell_char(6, 6),
-            self.cell_char(6, 7),
-            self.cell_char(6, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(7, 0),
-            self.cell_char(7, 1),
-            self.cell_char(7, 2),
-            self.cell_char(7, 3),
-            self.cell_char(7, 4),
-            self.cell_char(7, 5),
-            self.cell_char(7, 6),
-            self.cell_char(7, 7),
-            self.cell_char(7, 8)
-        )?;
-        writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤",)?;
-        writeln!(
-            f,
-            "│ {} │ {} │ {} ║ {} │ {} │ {} ║ {} │ {} │ {} │",
-            self.cell_char(8, 0),
-            self.cell_char(8, 1),
-            self.cell_char(8, 2),
-            self.cell_char(8, 3),
-            self.cell_char(8, 4),
-            self.cell_char(8, 5),
-            self.cell_char(8, 6),
-            self.cell_char(8, 7),
-            self.cell_char(8, 8)
-        )?;
-        writeln!(f, "└───┴───┴───╨───┴───┴───╨───┴───┴───┘",)?;
-        Ok(())
+        self.display().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboards::EASY;
+
+    fn easy_solution() -> [[u8; 9]; 9] {
+        let mut game = Game::new(EASY);
+        game.solve().unwrap();
+        game.to_array()
+    }
+
+    fn count_nodes(game: &Game, cap: usize) -> (usize, usize) {
+        let nodes = AtomicUsize::new(0);
+        let hooks = SearchHooks {
+            nodes: Some(&nodes),
+            ..SearchHooks::default()
+        };
+        let count = game.count_solutions_hooked(cap, &hooks);
+        (count, nodes.load(Ordering::Relaxed))
+    }
+
+    // Whether two games are the same down to the solver state `dump_state` leaves out.
+    fn assert_same(a: &Game, b: &Game) {
+        assert_eq!(a.dump_state(), b.dump_state());
+        assert_eq!(a.positions, b.positions);
+    }
+
+    // Sets and then unsets every candidate of every empty cell, checking each time that the game
+    // is back where it started.
+    fn assert_round_trips(game: &Game) {
+        for (y, x) in (0..81).map(Game::coords) {
+            if game.board[y][x].is_some() {
+                continue;
+            }
+            for i in (0..9).filter(|&i| game.cell_poss[y][x][i]) {
+                let mut after = *game;
+                after.set_cell(y, x, CellValue::from_index(i));
+                after.unset_cell(y, x);
+                assert_same(&after, game);
+            }
+        }
+    }
+
+    // Plays out random placements from an empty board, with a propagation sweep after each one
+    // until nothing changes, showing `check` the game after every placement and every sweep. Some
+    // placements are wrong, so this goes through contradictions as well as solutions.
+    pub(super) fn random_sweeps(seed: u64, mut check: impl FnMut(&Game)) {
+        let mut rng = SplitMix64::new(seed);
+        let mut game = Game::new(crate::gameboards::ZEROS);
+        loop {
+            while game.propagate_poss_to_board() {
+                check(&game);
+            }
+            let open = (0..81)
+                .map(Game::coords)
+                .filter(|&(y, x)| game.board[y][x].is_none() && !game.candidates((y, x)).is_empty())
+                .collect::<Vec<_>>();
+            if open.is_empty() {
+                return;
+            }
+            let (y, x) = open[rng.below(open.len())];
+            let values = game.candidates((y, x)).iter().collect::<Vec<_>>();
+            game.set_cell(y, x, values[rng.below(values.len())]);
+            check(&game);
+        }
+    }
+
+    #[test]
+    fn flags_match_a_recount_through_propagation() {
+        for seed in 0..200 {
+            random_sweeps(seed, |game| {
+                assert!(game.flags_match_board(), "seed {}", seed);
+            });
+        }
+    }
+
+    #[test]
+    fn unset_undoes_set() {
+        for &numbers in &[EASY, crate::gameboards::HARD, crate::gameboards::SEVENTEEN] {
+            assert_round_trips(&Game::new(numbers));
+        }
+    }
+
+    #[test]
+    fn unset_undoes_set_in_crowded_units() {
+        // Every row, column, and 3x3 with one or two cells left
+        let solution = easy_solution();
+        let mut numbers = solution;
+        for (y, x) in (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| (y + 4 * x) % 9 == 0)
+        {
+            numbers[y][x] = 0;
+        }
+        let game = Game::new(numbers);
+        assert_round_trips(&game);
+        // The last empty cell in a unit, and so the last value the unit was missing
+        let mut full = solution;
+        full[0][0] = 0;
+        assert_round_trips(&Game::new(full));
+    }
+
+    #[test]
+    fn unset_clears_logical_eliminations() {
+        let puzzle = crate::gameboards::X_CHAIN;
+        let steps = Game::new(puzzle).solve_logical();
+        let last = steps
+            .iter()
+            .position(|step| !step.eliminations.is_empty())
+            .expect("X_CHAIN needs an elimination");
+        let mut game = Game::new(puzzle);
+        for step in &steps[..=last] {
+            for &(y, x, cv) in &step.placements {
+                game.set_cell(y, x, cv);
+            }
+            for &(y, x, cv) in &step.eliminations {
+                game.eliminate(y, x, cv);
+            }
+        }
+        assert!(game.elims.iter().flatten().any(|elims| !elims.is_empty()));
+        // The same placements on a fresh game, with nothing crossed off
+        let mut fresh = Game::new(puzzle);
+        for (y, x, &cell) in game.iter_cells() {
+            if let (Some(cv), false) = (cell, game.givens[y][x]) {
+                fresh.set_cell(y, x, cv);
+            }
+        }
+        let (y, x) = (0..81)
+            .map(Game::coords)
+            .find(|&(y, x)| game.board[y][x].is_none())
+            .unwrap();
+        let cv = game.candidates((y, x)).iter().next().unwrap();
+        let mut after = game;
+        after.set_cell(y, x, cv);
+        after.unset_cell(y, x);
+        // Every elimination is gone, not just the ones the cell could have been behind.
+        assert!(after.elims.iter().flatten().all(|elims| elims.is_empty()));
+        assert_same(&after, &fresh);
+    }
+
+    #[test]
+    fn unset_keeps_restrictions() {
+        let solution = easy_solution();
+        let mut givens = solution;
+        let mut builder = GameBuilder::new();
+        for (y, x) in (0..81).map(Game::coords).filter(|&(y, x)| (y + x) % 3 == 0) {
+            givens[y][x] = 0;
+            let value = CellValue::new(solution[y][x]).unwrap();
+            let other = CellValue::from_index((usize::from(value) + 4) % 9);
+            builder = builder.candidates((y, x), [value, other].iter().copied().collect());
+        }
+        let game = builder.givens(givens).build().unwrap();
+        assert!(game
+            .crossed_off
+            .iter()
+            .flatten()
+            .any(|crossed| !crossed.is_empty()));
+        assert_round_trips(&game);
+    }
+
+    #[test]
+    fn seven_digit_puzzle_is_counted_without_searching() {
+        // 30 clues from a solution, all of them 1 to 7
+        let solution = easy_solution();
+        let mut numbers = [[0; 9]; 9];
+        for (y, x) in (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| solution[y][x] <= 7)
+            .take(30)
+        {
+            numbers[y][x] = solution[y][x];
+        }
+        let game = Game::new(numbers);
+        assert_eq!(game.distinct_given_digits(), 7);
+        assert_eq!(count_nodes(&game, 2), (2, 0));
+    }
+
+    #[test]
+    fn eight_digit_puzzle_is_searched() {
+        let mut numbers = easy_solution();
+        for n in numbers.iter_mut().flatten().filter(|n| **n == 9) {
+            *n = 0;
+        }
+        let game = Game::new(numbers);
+        assert_eq!(game.distinct_given_digits(), 8);
+        let (count, nodes) = count_nodes(&game, 2);
+        assert_eq!(count, 1);
+        assert!(nodes > 0);
+    }
+
+    #[test]
+    fn restrictions_stop_the_digit_swap_shortcut() {
+        // With the 1s and 2s gone, the 1s' cells can't take a 5 and the 2s' cells are left open.
+        // Only one of the two swaps fits the restrictions.
+        let solution = easy_solution();
+        let restrictions = solution.map(|row| {
+            row.map(|n| match n {
+                1 => 0b1_0001,
+                2 => 0x1ff,
+                n => 1 << usize::from(CellValue::new(n).unwrap()),
+            })
+        });
+        let game = Game::new_with_restrictions(restrictions).unwrap();
+        assert_eq!(game.distinct_given_digits(), 7);
+        assert_eq!(game.count_solutions(2), 1);
+        assert_eq!(game.solutions().count(), 1);
+        assert!(game.is_well_formed());
+    }
+
+    #[test]
+    fn crossing_off_stops_the_digit_swap_shortcut() {
+        let solution = easy_solution();
+        let mut numbers = solution;
+        for n in numbers.iter_mut().flatten().filter(|n| **n <= 2) {
+            *n = 0;
+        }
+        let mut game = Game::new(numbers);
+        assert_eq!(game.count_solutions(2), 2);
+        // Crossing 2 off every cell the 1s go in leaves only the solution.
+        for (y, x) in (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| solution[y][x] == 1)
+        {
+            game.eliminate_candidate((y, x), CellValue::Two).unwrap();
+        }
+        assert_eq!(game.count_solutions(2), 1);
+        assert_eq!(game.remaining_completions(2), 1);
+    }
+
+    // R1C1 can only be 1 or 2, and row 2 has nowhere for a 1 but R2C1, so R1C1 has to be 2.
+    fn crossed_off_empty_board() -> Game {
+        let mut game = Game::new(crate::gameboards::ZEROS);
+        for &cv in &CellValue::ALL[2..] {
+            game.eliminate_candidate((0, 0), cv).unwrap();
+        }
+        for x in 1..9 {
+            game.eliminate_candidate((1, x), CellValue::One).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn crossing_off_turns_off_symmetry_breaking() {
+        let game = crossed_off_empty_board();
+        let config = SolverConfig {
+            break_symmetry: true,
+            ..SolverConfig::default()
+        };
+        assert_eq!(game.count_solutions(1), 1);
+        assert_eq!(game.count_solutions_with_config(1, &config), 1);
+        let solution = game.solutions_with_config(&config).next().unwrap();
+        assert_eq!(solution.board[0][0], Some(CellValue::Two));
+        assert_eq!(solution.board[1][0], Some(CellValue::One));
+    }
+
+    #[test]
+    fn crossing_off_survives_place_and_erase() {
+        let mut game = Game::new(EASY);
+        let (coord, right, wrong) = wrong_placement(&game);
+        game.eliminate_candidate(coord, wrong).unwrap();
+        assert!(!game.candidates(coord).contains(wrong));
+        game.place(coord, right).unwrap();
+        assert_eq!(
+            game.eliminate_candidate(coord, wrong),
+            Err(ElimError::Filled)
+        );
+        // Emptying the cell brings back what the rules allow, but not what the player crossed off.
+        game.erase(coord).unwrap();
+        assert!(game.candidates(coord).contains(right));
+        assert!(!game.candidates(coord).contains(wrong));
+        game.restore_candidate(coord, wrong).unwrap();
+        assert!(game.candidates(coord).contains(wrong));
+        assert_eq!(
+            game.restore_candidate(coord, wrong),
+            Err(ElimError::NotCrossedOff)
+        );
+    }
+
+    #[test]
+    fn restoring_leaves_out_what_the_rules_rule_out() {
+        let mut game = Game::new(EASY);
+        let (coord, _, wrong) = wrong_placement(&game);
+        game.eliminate_candidate(coord, wrong).unwrap();
+        let peer = peers(coord.0, coord.1)
+            .find(|&(y, x)| game.board[y][x].is_none() && game.can_place((y, x), wrong).is_ok())
+            .unwrap();
+        game.place(peer, wrong).unwrap();
+        game.restore_candidate(coord, wrong).unwrap();
+        assert!(!game.candidates(coord).contains(wrong));
+        game.erase(peer).unwrap();
+        assert!(game.candidates(coord).contains(wrong));
+    }
+
+    #[test]
+    fn solve_keeps_to_crossed_off_candidates() {
+        let mut game = Game::new(EASY);
+        let (coord, right, _) = wrong_placement(&game);
+        game.eliminate_candidate(coord, right).unwrap();
+        assert_eq!(
+            game.solve_into(&mut Game::new(EASY)),
+            Err(SolveError::Unsolvable)
+        );
+        assert_eq!(game.count_solutions(2), 0);
+        game.restore_candidate(coord, right).unwrap();
+        game.solve().unwrap();
+        assert_eq!(game.to_array(), easy_solution());
+    }
+
+    #[test]
+    fn solve_gives_up_on_units_that_cant_be_completed() {
+        let mut game = crossed_off_empty_board();
+        let start = std::time::Instant::now();
+        game.solve().unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(game.board[0][0], Some(CellValue::Two));
+        assert_eq!(game.board[1][0], Some(CellValue::One));
+    }
+
+    #[test]
+    fn hardness_of_a_dead_end_is_negative_infinity() {
+        let mut game = Game::new(crate::gameboards::ZEROS);
+        for &cv in &CellValue::ALL {
+            game.eliminate_candidate((4, 4), cv).unwrap();
+        }
+        let estimate = game.estimate_hardness();
+        assert_eq!(estimate.empty_cells, 81);
+        assert_eq!(estimate.candidates, 80 * 9);
+        assert_eq!(estimate.log_search_space, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn seventeen_has_no_redundant_clues() {
+        let game = Game::new(crate::gameboards::SEVENTEEN);
+        assert!(game.redundant_clues().is_empty());
+        assert_eq!(game.essential_clues().len(), 17);
+    }
+
+    #[test]
+    fn easy_has_redundant_clues() {
+        let game = Game::new(EASY);
+        let redundant = game.redundant_clues();
+        assert!(redundant.len() > 1);
+        for &coord in &redundant {
+            assert_eq!(game.without_clue(coord).unwrap().count_solutions(2), 1);
+        }
+        let clues = game
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_some())
+            .count();
+        assert_eq!(redundant.len() + game.essential_clues().len(), clues);
+    }
+
+    #[test]
+    fn without_clue_matches_building_from_scratch() {
+        let mut rng = SplitMix64::new(184);
+        for named in crate::gameboards::with_solutions() {
+            let solution = match named.solution {
+                Some(solution) => solution,
+                None => continue,
+            };
+            // Takes clues out of the solution in a random order, all the way down to an empty
+            // board, comparing each step with a game built from the smaller board.
+            let mut numbers = solution;
+            let mut game = Game::new(numbers);
+            let mut order = (0..81).collect::<Vec<_>>();
+            for i in (1..81).rev() {
+                order.swap(i, rng.below(i + 1));
+            }
+            for coord in order.into_iter().map(Game::coords) {
+                game = game.without_clue(coord).unwrap();
+                numbers[coord.0][coord.1] = 0;
+                assert_same(&game, &Game::new(numbers));
+            }
+        }
+    }
+
+    #[test]
+    fn contradictions_are_reported_not_searched() {
+        let mut numbers = EASY;
+        let (y, x) = (0..81)
+            .map(Game::coords)
+            .find(|&(y, x)| EASY[y][x] == 0)
+            .unwrap();
+        let repeat = (0..9).map(|x| EASY[y][x]).find(|&n| n != 0).unwrap();
+        numbers[y][x] = repeat;
+        assert_eq!(Game::try_new(numbers).err(), Some(BoardError::Invalid));
+
+        // A board that starts out fine can still be crossed off into one with no answer.
+        let mut game = Game::try_new(EASY).unwrap();
+        for &cv in &CellValue::ALL {
+            game.eliminate_candidate((y, x), cv).unwrap();
+        }
+        assert_eq!(game.solve().err(), Some(SolveError::Invalid));
+        assert_eq!(game.solve_bfs(100).err(), Some(SolveError::Invalid));
+    }
+
+    #[test]
+    fn conflicts_are_reported_where_they_are() {
+        // Two 5s in the bottom right 3x3, the second at row 9, column 8, and a third 5 in its
+        // column.
+        let mut numbers = [[0; 9]; 9];
+        numbers[1][7] = 5;
+        numbers[6][6] = 5;
+        numbers[8][7] = 5;
+        let mut report = String::new();
+        assert!(!Game::from_numbers(numbers).check_rules(Some(&mut report)));
+        assert_eq!(
+            report,
+            "Conflict: col 8 has multiple 5s\n\
+             Conflict: 3x3 9 has multiple 5s\n\
+             Conflict found at R9C8\n"
+        );
+        assert_eq!(Game::parse_cell_name("R9C8"), Some((8, 7)));
+
+        let mut game = Game::new(EASY);
+        let (y, x) = (0..81)
+            .map(Game::coords)
+            .find(|&(y, x)| EASY[y][x] == 0)
+            .unwrap();
+        for &cv in &CellValue::ALL {
+            game.eliminate_candidate((y, x), cv).unwrap();
+        }
+        let mut report = String::new();
+        assert!(!game.check_rules(Some(&mut report)));
+        assert_eq!(
+            report,
+            format!("Cell R{}C{} has no possible values\n", y + 1, x + 1)
+        );
+    }
+
+    #[test]
+    fn legal_values_ignore_deductions() {
+        let mut game = Game::new(crate::gameboards::TOP95_1);
+        let step = game.apply_technique(Technique::LockedCandidates).unwrap();
+        let (y, x, cv) = step.eliminations[0];
+        assert!(!game.candidates((y, x)).contains(cv));
+        assert!(game.legal_values((y, x)).contains(cv));
+        assert_eq!(game.can_place((y, x), cv), Ok(()));
+        for (y, x, cell) in game.iter_cells() {
+            let (candidates, legal) = (game.candidates((y, x)), game.legal_values((y, x)));
+            if cell.is_some() {
+                assert!(legal.is_empty());
+            } else {
+                assert_eq!(candidates.bits() & !legal.bits(), 0);
+                for cv in Candidates::all().iter().filter(|&cv| !legal.contains(cv)) {
+                    let conflict = match game.can_place((y, x), cv) {
+                        Err(PlaceError::Conflict { row, col }) => (row, col),
+                        other => panic!("{:?}", other),
+                    };
+                    assert!(Game::are_peers((y, x), conflict));
+                    assert_eq!(game.board[conflict.0][conflict.1], Some(cv));
+                }
+            }
+        }
+    }
+
+    // SEVENTEEN without its 2 in row 6, which leaves it with 145 solutions.
+    const AMBIGUOUS: &str =
+        "...8.....789.1...6.....61....7....5.5.87.93.4.4.........32.....8...7.439.....1...";
+
+    #[test]
+    fn solutions_come_out_sorted() {
+        let game = AMBIGUOUS.parse::<Game>().unwrap();
+        let lines = game.solutions().map(|s| s.to_line()).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 145);
+        assert_eq!(game.count_solutions(1000), 145);
+        assert!(lines.windows(2).all(|pair| pair[0] < pair[1]));
+        for line in &lines {
+            let solved = line.parse::<Game>().unwrap();
+            assert!(solved.solved());
+            for (y, x, cell) in game.iter_cells() {
+                assert!(cell.is_none() || solved.board[y][x] == *cell);
+            }
+        }
+    }
+
+    #[test]
+    fn solution_count_estimates() {
+        let mut rng = SplitMix64::new(7);
+        let easy = Game::new(EASY).estimate_solution_count(10, &mut rng);
+        assert!((easy - 1.0).abs() < 1e-9, "{}", easy);
+        let ambiguous = AMBIGUOUS.parse::<Game>().unwrap();
+        let estimate = ambiguous.estimate_solution_count(2000, &mut rng);
+        assert!(
+            (145.0 / 2.0..=145.0 * 2.0).contains(&estimate),
+            "{}",
+            estimate
+        );
+        assert_eq!(ambiguous.estimate_solution_count(0, &mut rng), 0.0);
+    }
+
+    // An empty cell of EASY along with its right value and a wrong one that can still be placed
+    fn wrong_placement(game: &Game) -> (Coord, CellValue, CellValue) {
+        let solution = easy_solution();
+        (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| game.board[y][x].is_none())
+            .find_map(|(y, x)| {
+                let right = CellValue::new(solution[y][x]).unwrap();
+                let wrong = game.candidates((y, x)).iter().find(|&cv| cv != right)?;
+                Some(((y, x), right, wrong))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn reveal_needs_a_unique_solution() {
+        let mut numbers = EASY;
+        for row in numbers.iter_mut().skip(3) {
+            *row = [0; 9];
+        }
+        let mut game = Game::new(numbers);
+        let before = game.dump_state();
+        assert_eq!(game.reveal((8, 8)), Err(RevealError::NotUnique));
+        assert_eq!(game.dump_state(), before);
+        // Only the givens count, so a player's entries can't make it unique.
+        let mut solved = Game::new(easy_solution());
+        for (y, x) in (0..81).map(Game::coords).filter(|&(y, _)| y >= 3) {
+            solved.givens[y][x] = false;
+        }
+        assert_eq!(solved.reveal((8, 8)), Err(RevealError::NotUnique));
+    }
+
+    #[test]
+    fn reveal_leaves_givens_alone() {
+        let mut game = Game::new(EASY);
+        let (y, x) = (0..81)
+            .map(Game::coords)
+            .find(|&(y, x)| EASY[y][x] != 0)
+            .unwrap();
+        assert_eq!(game.reveal((y, x)), Err(RevealError::Given));
+        assert_eq!(game.reveal((9, 0)), Err(RevealError::OutOfBounds));
+        assert_eq!(game.to_array(), EASY);
+    }
+
+    #[test]
+    fn reveal_replaces_a_conflicting_entry() {
+        let mut game = Game::new(EASY);
+        let (coord, right, wrong) = wrong_placement(&game);
+        game.place(coord, wrong).unwrap();
+        let reveal = game.reveal(coord).unwrap();
+        assert_eq!(reveal.value, right);
+        assert_eq!(reveal.replaced, Some(wrong));
+        assert!(reveal.cleared.is_empty());
+        assert_eq!(game.board[coord.0][coord.1], Some(right));
+        // The same value somewhere it's wrong, in a cell that sees this one, gets emptied.
+        let mut game = Game::new(EASY);
+        let (y, x) = coord;
+        let peer = peers(y, x)
+            .find(|&(py, px)| {
+                game.board[py][px].is_none() && game.candidates((py, px)).contains(right)
+            })
+            .unwrap();
+        game.place(peer, right).unwrap();
+        let reveal = game.reveal(coord).unwrap();
+        assert_eq!(reveal.replaced, None);
+        assert_eq!(reveal.cleared, [peer]);
+        assert_eq!(game.board[peer.0][peer.1], None);
+        assert!(game.is_valid(false));
     }
 }
@@ -0,0 +1,127 @@
+// Bands and stacks: the three rows, or three columns, that run through the same three 3x3s. A band
+// can be looked at on its own, since the rules inside it only involve its rows and 3x3s.
+
+use super::{BoardError, CellValue, Game};
+use std::collections::HashMap;
+
+/// The cells of a band, its three rows from top to bottom.
+pub type Band = [[Option<CellValue>; 9]; 3];
+
+impl Game {
+    /// The cells of band `i`, rows `3 * i` to `3 * i + 2`, with bands numbered from the top.
+    pub fn band(&self, i: usize) -> Band {
+        let mut band = [[None; 9]; 3];
+        band.copy_from_slice(&self.board[3 * i..3 * i + 3]);
+        band
+    }
+
+    /// The cells of stack `i`, columns `3 * i` to `3 * i + 2`, with stacks numbered from the left.
+    /// Each entry is one row of the stack.
+    pub fn stack(&self, i: usize) -> [[Option<CellValue>; 3]; 9] {
+        let mut stack = [[None; 3]; 9];
+        for (row, cells) in stack.iter_mut().enumerate() {
+            cells.copy_from_slice(&self.board[row][3 * i..3 * i + 3]);
+        }
+        stack
+    }
+
+    /// A new game with band `i` swapped out for `cells` and the rest of the board the same. It's
+    /// built from the values alone, as with `Game::try_new`, so anything crossed off in this game
+    /// is left behind, and it fails the same way if the new board breaks the rules.
+    pub fn replace_band(&self, i: usize, cells: &Band) -> Result<Game, BoardError> {
+        let mut numbers = self.to_array();
+        for (row, band_row) in numbers[3 * i..3 * i + 3].iter_mut().zip(cells) {
+            for (n, cell) in row.iter_mut().zip(band_row) {
+                *n = cell.map_or(0, |cv| cv as u8);
+            }
+        }
+        Game::try_new(numbers)
+    }
+}
+
+/// Counts the ways the empty cells of a band can be filled in so that each row holds every value
+/// and so does each 3x3, ignoring the rest of the board. Filled cells are kept as they are, so a
+/// band that already breaks the rules has 0 ways. An empty band has 948,109,639,680, the full
+/// count with no symmetry taken out: 9! ways to fill in its top 3x3, times 2,612,736 for the
+/// rest.
+///
+/// The count comes from a search that fills in the band a column at a time and remembers, for
+/// each column boundary, how many ways there were to finish from each combination of what the
+/// rows and the current 3x3 already hold.
+pub fn band_completions(band: &Band) -> u64 {
+    // Values each row, and each 3x3, holds in its filled cells, which the empty cells can't take.
+    let mut row_givens = [0u16; 3];
+    let mut sqr_givens = [0u16; 3];
+    for (y, row) in band.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            if let Some(cv) = cell {
+                row_givens[y] |= 1 << usize::from(*cv);
+                sqr_givens[x / 3] |= 1 << usize::from(*cv);
+            }
+        }
+    }
+    let givens = Givens {
+        band,
+        rows: row_givens,
+        sqrs: sqr_givens,
+    };
+    givens.count(0, [0; 3], 0, &mut HashMap::new())
+}
+
+struct Givens<'a> {
+    band: &'a Band,
+    rows: [u16; 3],
+    sqrs: [u16; 3],
+}
+
+impl Givens<'_> {
+    // The ways to fill in columns `col` onward, given the values already used in each row and in
+    // the current 3x3.
+    fn count(
+        &self,
+        col: usize,
+        rows: [u16; 3],
+        sqr: u16,
+        memo: &mut HashMap<(usize, [u16; 3], u16), u64>,
+    ) -> u64 {
+        if col == 9 {
+            return 1;
+        }
+        if let Some(&count) = memo.get(&(col, rows, sqr)) {
+            return count;
+        }
+        let mut count = 0;
+        self.fill(col, 0, rows, sqr, memo, &mut count);
+        memo.insert((col, rows, sqr), count);
+        count
+    }
+
+    // Tries every value for the cell at row `y` of column `col`, moving down the column and then
+    // on to the next one.
+    fn fill(
+        &self,
+        col: usize,
+        y: usize,
+        rows: [u16; 3],
+        sqr: u16,
+        memo: &mut HashMap<(usize, [u16; 3], u16), u64>,
+        count: &mut u64,
+    ) {
+        if y == 3 {
+            // A 3x3 is full once its last column is, so the next one starts out empty.
+            let sqr = if col % 3 == 2 { 0 } else { sqr };
+            *count += self.count(col + 1, rows, sqr, memo);
+            return;
+        }
+        let used = rows[y] | sqr;
+        let allowed = match self.band[y][col] {
+            Some(cv) => 1 << usize::from(cv),
+            None => 0x1ff & !self.rows[y] & !self.sqrs[col / 3],
+        };
+        for i in (0..9).filter(|&i| allowed & !used & 1 << i != 0) {
+            let mut rows = rows;
+            rows[y] |= 1 << i;
+            self.fill(col, y + 1, rows, sqr | 1 << i, memo, count);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// A limit on how much help a player can ask for, kept apart from the game so that nothing done to
+// the board, like erasing a cell, gives hints back.
+
+use super::{Game, Hint};
+use std::error::Error;
+use std::fmt;
+
+/// The kinds of help a hint budget is charged for.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HintKind {
+    /// Pointing out a cell a technique can fill in, as from `Game::available_hints`
+    Technique,
+    /// Filling in a cell outright
+    Reveal,
+}
+
+/// The hint budget didn't have enough left for the hint asked for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HintsExhausted {
+    pub remaining: u32,
+    pub cost: u32,
+}
+
+impl fmt::Display for HintsExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "out of hints: {} left, this one costs {}",
+            self.remaining, self.cost
+        )
+    }
+}
+
+impl Error for HintsExhausted {}
+
+/// How many hints a player has left, with each kind of hint costing its own amount. Technique
+/// hints cost 1 and reveals 3 unless set otherwise.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HintBudget {
+    remaining: u32,
+    technique_cost: u32,
+    reveal_cost: u32,
+}
+
+impl HintBudget {
+    pub fn new(budget: u32) -> Self {
+        HintBudget {
+            remaining: budget,
+            technique_cost: 1,
+            reveal_cost: 3,
+        }
+    }
+
+    /// Sets what each kind of hint costs.
+    pub fn with_costs(mut self, technique: u32, reveal: u32) -> Self {
+        self.technique_cost = technique;
+        self.reveal_cost = reveal;
+        self
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Replaces what's left with a new amount, keeping the costs.
+    pub fn set_remaining(&mut self, budget: u32) {
+        self.remaining = budget;
+    }
+
+    pub fn cost(&self, kind: HintKind) -> u32 {
+        match kind {
+            HintKind::Technique => self.technique_cost,
+            HintKind::Reveal => self.reveal_cost,
+        }
+    }
+
+    /// Whether there's enough left for a hint of this kind.
+    pub fn can_afford(&self, kind: HintKind) -> bool {
+        self.cost(kind) <= self.remaining
+    }
+
+    /// Charges for a hint, returning what's left afterwards. Nothing is charged if there isn't
+    /// enough left.
+    pub fn spend(&mut self, kind: HintKind) -> Result<u32, HintsExhausted> {
+        let cost = self.cost(kind);
+        if cost > self.remaining {
+            return Err(HintsExhausted {
+                remaining: self.remaining,
+                cost,
+            });
+        }
+        self.remaining -= cost;
+        Ok(self.remaining)
+    }
+}
+
+impl Game {
+    /// The first of `available_hints`, charged to the budget as a technique hint. The budget is
+    /// only charged when there's a hint to give, so `Ok(None)` costs nothing.
+    pub fn budgeted_hint(&self, budget: &mut HintBudget) -> Result<Option<Hint>, HintsExhausted> {
+        if !budget.can_afford(HintKind::Technique) {
+            return Err(HintsExhausted {
+                remaining: budget.remaining(),
+                cost: budget.cost(HintKind::Technique),
+            });
+        }
+        let hint = self.available_hints().into_iter().next();
+        if hint.is_some() {
+            budget.spend(HintKind::Technique)?;
+        }
+        Ok(hint)
+    }
+}
@@ -0,0 +1,61 @@
+// A single place to describe a game before building it, so new options don't each need their own
+// constructor.
+
+use super::{BoardError, Candidates, Coord, Game};
+
+/// Describes a game to build: its givens, plus any cells whose candidates are limited from the
+/// start, as in puzzles that come with some pencil marks filled in.
+#[derive(Clone, Debug, Default)]
+pub struct GameBuilder {
+    givens: [[u8; 9]; 9],
+    candidates: Vec<(Coord, Candidates)>,
+}
+
+impl GameBuilder {
+    pub fn new() -> Self {
+        GameBuilder::default()
+    }
+
+    /// Sets the givens, row by row, with 0 for an empty cell.
+    pub fn givens(mut self, givens: [[u8; 9]; 9]) -> Self {
+        self.givens = givens;
+        self
+    }
+
+    /// Limits an empty cell to the given candidates. For a given cell, its value just has to be one
+    /// of them.
+    pub fn candidates(mut self, coord: Coord, candidates: Candidates) -> Self {
+        self.candidates.push((coord, candidates));
+        self
+    }
+
+    /// Builds the game, checking the givens the same way as `Game::try_new`. Fails with
+    /// `BoardError::Invalid` if a limited cell is out of bounds, a given isn't among its cell's
+    /// candidates, or the limits leave some empty cell with no candidates at all.
+    pub fn build(&self) -> Result<Game, BoardError> {
+        let mut game = Game::try_new(self.givens)?;
+        for &((row, col), candidates) in &self.candidates {
+            if row >= 9 || col >= 9 {
+                return Err(BoardError::Invalid);
+            }
+            if let Some(cv) = game.board[row][col] {
+                if !candidates.contains(cv) {
+                    return Err(BoardError::Invalid);
+                }
+                continue;
+            }
+            for cv in Candidates::all()
+                .iter()
+                .filter(|&cv| !candidates.contains(cv))
+            {
+                game.crossed_off[row][col].insert(cv);
+                game.set_poss(row, col, usize::from(cv), false);
+            }
+        }
+        if game.is_valid(false) {
+            Ok(game)
+        } else {
+            Err(BoardError::Invalid)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use super::CellValue;
+use std::fmt;
+
+/// A set of cell values, stored as a bitmask with bit `i` for the value `i + 1`.
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
+pub struct Candidates(u16);
+
+impl Candidates {
+    pub fn empty() -> Self {
+        Candidates(0)
+    }
+
+    pub fn all() -> Self {
+        Candidates(0x1ff)
+    }
+
+    pub(crate) fn from_bools(bools: &[bool; 9]) -> Self {
+        bools
+            .iter()
+            .enumerate()
+            .filter(|&(_, &b)| b)
+            .fold(Candidates(0), |acc, (i, _)| Candidates(acc.0 | 1 << i))
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(self, cv: CellValue) -> bool {
+        self.0 & 1 << usize::from(cv) != 0
+    }
+
+    pub fn insert(&mut self, cv: CellValue) {
+        self.0 |= 1 << usize::from(cv);
+    }
+
+    pub fn remove(&mut self, cv: CellValue) {
+        self.0 &= !(1 << usize::from(cv));
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The values in the set, smallest first.
+    pub fn iter(self) -> impl Iterator<Item = CellValue> {
+        (0..9)
+            .filter(move |&i| self.0 & 1 << i != 0)
+            .map(CellValue::from_index)
+    }
+}
+
+impl fmt::Debug for Candidates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(self.iter().map(|cv| cv as u8))
+            .finish()
+    }
+}
@@ -0,0 +1,232 @@
+// Summaries of a game's clues, for catching broken puzzles early, and clue patterns that can be
+// carried over to other solutions.
+
+use super::{peers, CellValue, Coord, Game};
+use std::error::Error;
+use std::fmt;
+
+/// Counts of the filled cells in a game, from `Game::clue_report`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClueReport {
+    /// How many times each value appears, indexed by value minus 1
+    pub digit_counts: [usize; 9],
+    /// Values that don't appear at all
+    pub missing_digits: Vec<CellValue>,
+    pub row_counts: [usize; 9],
+    pub col_counts: [usize; 9],
+    /// Counts for each 3x3, numbered left to right and then top to bottom
+    pub box_counts: [usize; 9],
+    /// The top left and bottom right corners of the smallest rectangle holding every clue, or
+    /// `None` if there are no clues
+    pub bounds: Option<(Coord, Coord)>,
+}
+
+impl ClueReport {
+    /// Whether some row, column, or 3x3 has no clues in it.
+    pub fn has_empty_unit(&self) -> bool {
+        self.row_counts
+            .iter()
+            .chain(&self.col_counts)
+            .chain(&self.box_counts)
+            .any(|&n| n == 0)
+    }
+
+    /// Whether the clues alone show that the game can't have a unique solution. If two values
+    /// are missing, swapping them in any solution gives another one.
+    pub fn certainly_ambiguous(&self) -> bool {
+        self.missing_digits.len() >= 2
+    }
+}
+
+/// Reasons `Game::apply_mask` couldn't make a puzzle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MaskError {
+    /// The solution given has empty cells or breaks the rules
+    NotSolved,
+    /// The masked cells don't pin down a single solution
+    NotUnique,
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaskError::NotSolved => write!(f, "the solution isn't a complete, valid board"),
+            MaskError::NotUnique => write!(f, "the masked clues allow more than one solution"),
+        }
+    }
+}
+
+impl Error for MaskError {}
+
+impl Game {
+    /// Which cells are filled, as a pattern that `apply_mask` can put on another solution.
+    pub fn clue_mask(&self) -> [[bool; 9]; 9] {
+        self.board.map(|row| row.map(|cell| cell.is_some()))
+    }
+
+    /// Makes a puzzle from a solved board by keeping only the cells set in `mask`, so one
+    /// puzzle's clue pattern can be reused with a new solution. The puzzle is only returned if it
+    /// has exactly one solution.
+    pub fn apply_mask(solution: &Game, mask: &[[bool; 9]; 9]) -> Result<Game, MaskError> {
+        if solution.clue_count() != 81 || !solution.is_valid(false) {
+            return Err(MaskError::NotSolved);
+        }
+        let mut numbers = solution.to_array();
+        for (y, x) in (0..81).map(Game::coords) {
+            if !mask[y][x] {
+                numbers[y][x] = 0;
+            }
+        }
+        let puzzle = Game::new(numbers);
+        match puzzle.count_solutions(2) {
+            1 => Ok(puzzle),
+            _ => Err(MaskError::NotUnique),
+        }
+    }
+
+    /// How many candidates a clue rules out on its own: the empty cells it sees that could take
+    /// its value if it were gone, given the other clues and anything crossed off. Returns 0 for an
+    /// empty cell or one outside the board.
+    pub fn clue_impact(&self, row: usize, col: usize) -> usize {
+        let without = match self.without_clue((row, col)) {
+            Ok(without) => without,
+            Err(_) => return 0,
+        };
+        let cv = self.board[row][col].unwrap();
+        peers(row, col)
+            .filter(|&(y, x)| {
+                self.board[y][x].is_none()
+                    && !(without.rows_flags[y][cv]
+                        || without.cols_flags[x][cv]
+                        || without.sqrs_flags[without.sqrs_ind(y, x)][cv]
+                        || self.elims[y][x].contains(cv)
+                        || self.crossed_off[y][x].contains(cv))
+            })
+            .count()
+    }
+
+    /// Counts the clues by value, row, column, and 3x3, and finds the area they cover.
+    pub fn clue_report(&self) -> ClueReport {
+        let mut report = ClueReport {
+            digit_counts: [0; 9],
+            missing_digits: Vec::new(),
+            row_counts: [0; 9],
+            col_counts: [0; 9],
+            box_counts: [0; 9],
+            bounds: None,
+        };
+        for (y, x, cell) in self.iter_cells() {
+            let cv = match *cell {
+                Some(cv) => cv,
+                None => continue,
+            };
+            report.digit_counts[usize::from(cv)] += 1;
+            report.row_counts[y] += 1;
+            report.col_counts[x] += 1;
+            report.box_counts[self.sqrs_ind(y, x)] += 1;
+            report.bounds = Some(match report.bounds {
+                Some(((top, left), (bottom, right))) => {
+                    ((top.min(y), left.min(x)), (bottom.max(y), right.max(x)))
+                }
+                None => ((y, x), (y, x)),
+            });
+        }
+        report.missing_digits = (0..9)
+            .filter(|&i| report.digit_counts[i] == 0)
+            .map(CellValue::from_index)
+            .collect();
+        report
+    }
+
+    /// How many different values the filled cells hold. With 7 or fewer, two values appear
+    /// nowhere on the board, and swapping them in any solution gives another one, so the game
+    /// can't have a unique solution. With 8 the last value is pinned down by the others and
+    /// there's no such swap.
+    pub fn distinct_given_digits(&self) -> usize {
+        CellValue::ALL
+            .iter()
+            .filter(|&&cv| (0..9).any(|row| self.rows_flags[row][cv]))
+            .count()
+    }
+
+    /// Whether the game's solution has a deadly rectangle that the clues barely block: four cells
+    /// in two rows, two columns, and two 3x3s, holding `a b` in one row and `b a` in the other,
+    /// with at most one of them a clue. With no clues on it, the two values can be swapped for a
+    /// second solution. With one, that clue alone keeps the solution unique, so removing it or
+    /// getting it wrong breaks the puzzle. Filled cells count as clues, and a game with several
+    /// solutions is checked against the first one found. A game with no solution has no pattern.
+    pub fn has_deadly_pattern(&self) -> bool {
+        let solution = match self.solved_array() {
+            Some(solution) => solution,
+            None => return false,
+        };
+        let clue = |y: usize, x: usize| self.board[y][x].is_some() as usize;
+        for (top, bottom) in (0..9).flat_map(|t| (t + 1..9).map(move |b| (t, b))) {
+            // Rows in different bands would put the rectangle across four 3x3s, so the columns
+            // then have to share a stack.
+            let same_band = top / 3 == bottom / 3;
+            for left in 0..9 {
+                for right in left + 1..9 {
+                    if !same_band && left / 3 != right / 3 {
+                        continue;
+                    }
+                    let (a, b) = (solution[top][left], solution[top][right]);
+                    if solution[bottom][left] != b || solution[bottom][right] != a {
+                        continue;
+                    }
+                    let clues = clue(top, left)
+                        + clue(top, right)
+                        + clue(bottom, left)
+                        + clue(bottom, right);
+                    if clues <= 1 {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{CellValue, Game};
+    use crate::gameboards::{EASY, SEVENTEEN};
+
+    #[test]
+    fn seventeen_clue_counts() {
+        let report = Game::new(SEVENTEEN).clue_report();
+        assert_eq!(report.digit_counts, [3, 2, 2, 2, 2, 3, 1, 1, 1]);
+        assert!(report.missing_digits.is_empty());
+        assert_eq!(report.row_counts, [2, 1, 1, 3, 2, 1, 3, 2, 2]);
+        assert_eq!(report.col_counts, [3, 2, 1, 3, 2, 0, 3, 2, 1]);
+        assert_eq!(report.box_counts, [1, 2, 1, 2, 1, 3, 3, 2, 2]);
+        assert_eq!(report.bounds, Some(((0, 0), (8, 8))));
+        // Nothing in column 6, and yet a unique solution.
+        assert!(report.has_empty_unit());
+        assert!(!report.certainly_ambiguous());
+
+        let empty = Game::new([[0; 9]; 9]).clue_report();
+        assert_eq!(empty.missing_digits, CellValue::ALL);
+        assert_eq!(empty.bounds, None);
+    }
+
+    #[test]
+    fn two_missing_digits_make_a_game_ambiguous() {
+        let solution = Game::new(EASY).solved_array().unwrap();
+        let without =
+            |gone: &[u8]| solution.map(|row| row.map(|n| if gone.contains(&n) { 0 } else { n }));
+
+        let one_gone = Game::new(without(&[1]));
+        let report = one_gone.clue_report();
+        assert_eq!(report.missing_digits, [CellValue::One]);
+        assert!(!report.certainly_ambiguous());
+        assert_eq!(one_gone.count_solutions(2), 1);
+
+        let two_gone = Game::new(without(&[1, 2]));
+        let report = two_gone.clue_report();
+        assert_eq!(report.missing_digits, [CellValue::One, CellValue::Two]);
+        assert!(report.certainly_ambiguous());
+        assert_eq!(two_gone.count_solutions(2), 2);
+    }
+}
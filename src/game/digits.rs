@@ -0,0 +1,141 @@
+// Converting between values and the positions 0 to 8 that stand for them in arrays and bitmasks,
+// where the value `n` is at position `n - 1`. Everything else goes through the helpers here
+// rather than adding or taking away 1 itself.
+
+use super::{Candidates, CellValue};
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+
+impl CellValue {
+    /// Every value, smallest first, so `CellValue::ALL[i]` is the value at position `i`.
+    pub const ALL: [CellValue; 9] = [
+        CellValue::One,
+        CellValue::Two,
+        CellValue::Three,
+        CellValue::Four,
+        CellValue::Five,
+        CellValue::Six,
+        CellValue::Seven,
+        CellValue::Eight,
+        CellValue::Nine,
+    ];
+
+    /// The value at position `i` of a `DigitArray`, or bit `i` of a mask, which is `i + 1`. The
+    /// reverse of `usize::from`. Panics if `i` is 9 or more.
+    pub fn from_index(i: usize) -> CellValue {
+        CellValue::ALL[i]
+    }
+
+    /// The value a digit from `'1'` to `'9'` stands for. Anything else, `'0'` included, is `None`.
+    pub fn from_char(ch: char) -> Option<CellValue> {
+        ch.to_digit(10).and_then(|n| CellValue::new(n as u8))
+    }
+
+    /// The digit from `'1'` to `'9'` for this value. The reverse of `from_char`.
+    pub fn to_char(self) -> char {
+        char::from(b'0' + self as u8)
+    }
+}
+
+impl From<CellValue> for usize {
+    fn from(other: CellValue) -> Self {
+        (other as u8 - 1) as usize
+    }
+}
+
+/// One entry for each value, indexed by `CellValue` directly. It dereferences to the plain
+/// array underneath, where the entry for a value is at `usize::from(cv)`.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DigitArray<T>(pub [T; 9]);
+
+impl<T> DigitArray<T> {
+    /// The entries along with the values they're for, smallest value first.
+    pub fn iter_values(&self) -> impl Iterator<Item = (CellValue, &T)> + '_ {
+        CellValue::ALL.iter().copied().zip(self.0.iter())
+    }
+}
+
+impl<T> Index<CellValue> for DigitArray<T> {
+    type Output = T;
+
+    fn index(&self, cv: CellValue) -> &T {
+        &self.0[usize::from(cv)]
+    }
+}
+
+impl<T> IndexMut<CellValue> for DigitArray<T> {
+    fn index_mut(&mut self, cv: CellValue) -> &mut T {
+        &mut self.0[usize::from(cv)]
+    }
+}
+
+impl<T: PartialEq> PartialEq<[T; 9]> for DigitArray<T> {
+    fn eq(&self, other: &[T; 9]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T> Deref for DigitArray<T> {
+    type Target = [T; 9];
+
+    fn deref(&self) -> &[T; 9] {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for DigitArray<T> {
+    fn deref_mut(&mut self) -> &mut [T; 9] {
+        &mut self.0
+    }
+}
+
+impl From<DigitArray<bool>> for Candidates {
+    fn from(flags: DigitArray<bool>) -> Self {
+        flags
+            .iter_values()
+            .filter(|&(_, &b)| b)
+            .map(|(cv, _)| cv)
+            .collect()
+    }
+}
+
+impl From<Candidates> for DigitArray<bool> {
+    fn from(candidates: Candidates) -> Self {
+        let mut flags = DigitArray([false; 9]);
+        for cv in candidates.iter() {
+            flags[cv] = true;
+        }
+        flags
+    }
+}
+
+impl std::iter::FromIterator<CellValue> for Candidates {
+    fn from_iter<I: IntoIterator<Item = CellValue>>(values: I) -> Self {
+        let mut candidates = Candidates::empty();
+        for cv in values {
+            candidates.insert(cv);
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_round_trip() {
+        for (i, &cv) in CellValue::ALL.iter().enumerate() {
+            assert_eq!(CellValue::from_index(i), cv);
+            assert_eq!(usize::from(cv), i);
+            assert_eq!(CellValue::from_char(cv.to_char()), Some(cv));
+        }
+        let digits = CellValue::ALL
+            .iter()
+            .map(|cv| cv.to_char())
+            .collect::<String>();
+        assert_eq!(digits, "123456789");
+        for ch in ['0', '.', 'a', ' ', '٣'] {
+            assert_eq!(CellValue::from_char(ch), None);
+        }
+    }
+}
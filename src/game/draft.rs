@@ -0,0 +1,166 @@
+// A board that takes any values at all, for editors where someone is partway through typing in a
+// puzzle and may have a mistake on the board for a while. What's wrong with it is kept up to date
+// as cells change, and it only becomes a `Game` once nothing is.
+
+use super::text::write_grid;
+use super::{peers, CellValue, Coord, Game, PlaceError};
+use std::fmt;
+
+/// Something about a draft board that keeps it from being a game.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// Two cells in the same row, column, or 3x3 hold the same value. The cells are in board
+    /// order.
+    Repeated {
+        value: CellValue,
+        cells: (Coord, Coord),
+    },
+    /// An empty cell whose row, column, and 3x3 already hold every value between them
+    NoCandidates(Coord),
+}
+
+impl Violation {
+    /// Whether the cell is one of the ones at fault.
+    pub fn involves(&self, coord: Coord) -> bool {
+        match *self {
+            Violation::Repeated { cells, .. } => cells.0 == coord || cells.1 == coord,
+            Violation::NoCandidates(cell) => cell == coord,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::Repeated {
+                value,
+                cells: (a, b),
+            } => write!(
+                f,
+                "{} and {} both have {}",
+                Game::cell_name(a.0, a.1),
+                Game::cell_name(b.0, b.1),
+                value as u8
+            ),
+            Violation::NoCandidates((row, col)) => {
+                write!(f, "{} has no possible values", Game::cell_name(row, col))
+            }
+        }
+    }
+}
+
+/// A board being typed in, which unlike a `Game` can break the rules. Every change updates the
+/// list of violations, looking only at the changed cell and its peers, and `try_finalize` turns
+/// the board into a game once the list is empty.
+///
+/// Formatting it with `{}` draws the same grid as a `Game`, with the cells at fault in brackets.
+#[derive(Clone, Debug, Default)]
+pub struct DraftBoard {
+    cells: [[Option<CellValue>; 9]; 9],
+    violations: Vec<Violation>,
+}
+
+impl DraftBoard {
+    /// An empty board.
+    pub fn new() -> Self {
+        DraftBoard::default()
+    }
+
+    /// A draft with the same values as a game.
+    pub fn from_game(game: &Game) -> Self {
+        let mut draft = DraftBoard::new();
+        for (y, x, &cell) in game.iter_cells() {
+            let _ = draft.set((y, x), cell);
+        }
+        draft
+    }
+
+    pub fn get(&self, (row, col): Coord) -> Option<CellValue> {
+        self.cells.get(row)?.get(col).copied().flatten()
+    }
+
+    /// Fills in or empties a cell, whatever else is on the board. This only fails for cells
+    /// outside the board.
+    pub fn set(&mut self, (row, col): Coord, cell: Option<CellValue>) -> Result<(), PlaceError> {
+        if row >= 9 || col >= 9 {
+            return Err(PlaceError::OutOfBounds);
+        }
+        if self.cells[row][col] == cell {
+            return Ok(());
+        }
+        // Only the cell and its peers can be affected, so anything about them is checked again.
+        let affected = |coord: Coord| coord == (row, col) || Game::are_peers(coord, (row, col));
+        self.violations.retain(|v| match *v {
+            Violation::Repeated { .. } => !v.involves((row, col)),
+            Violation::NoCandidates(coord) => !affected(coord),
+        });
+        self.cells[row][col] = cell;
+        if let Some(value) = cell {
+            let repeats = peers(row, col)
+                .filter(|&(y, x)| self.cells[y][x] == Some(value))
+                .collect::<Vec<_>>();
+            for peer in repeats {
+                let cells = if peer < (row, col) {
+                    (peer, (row, col))
+                } else {
+                    ((row, col), peer)
+                };
+                self.violations.push(Violation::Repeated { value, cells });
+            }
+        }
+        for (y, x) in std::iter::once((row, col)).chain(peers(row, col)) {
+            if self.has_no_candidates((y, x)) {
+                self.violations.push(Violation::NoCandidates((y, x)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Empties a cell.
+    pub fn clear(&mut self, coord: Coord) -> Result<(), PlaceError> {
+        self.set(coord, None)
+    }
+
+    /// Everything currently wrong with the board, in no particular order.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Whether nothing is wrong with the board, so `try_finalize` will succeed.
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Makes a game from the board, with every filled cell as a given, the same as `Game::new`
+    /// would. Fails with the violations if there are any.
+    pub fn try_finalize(&self) -> Result<Game, Vec<Violation>> {
+        if !self.is_clean() {
+            return Err(self.violations.clone());
+        }
+        let mut numbers = [[0; 9]; 9];
+        for (row, cells) in numbers.iter_mut().zip(&self.cells) {
+            for (n, cell) in row.iter_mut().zip(cells) {
+                *n = cell.map_or(0, |cv| cv as u8);
+            }
+        }
+        Ok(Game::new(numbers))
+    }
+
+    fn has_no_candidates(&self, (row, col): Coord) -> bool {
+        self.cells[row][col].is_none()
+            && peers(row, col)
+                .filter_map(|(y, x)| self.cells[y][x])
+                .fold(0u16, |seen, cv| seen | 1 << usize::from(cv))
+                == 0x1ff
+    }
+}
+
+impl fmt::Display for DraftBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_grid(f, |y, x| {
+            let ch = self.cells[y][x].map_or(' ', CellValue::to_char);
+            let at_fault = self.violations.iter().any(|v| v.involves((y, x)));
+            (ch, at_fault)
+        })
+    }
+}
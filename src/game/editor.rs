@@ -0,0 +1,168 @@
+// Building a puzzle one clue at a time, keeping track of whether it still has a unique solution
+// without recounting after every edit when the answer already follows from the last one.
+
+use super::{CellValue, Coord, EraseError, Game, PlaceError, Reveal, RevealError};
+
+/// Whether a puzzle has exactly one solution, from `PuzzleEditor`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Unique,
+    Multiple,
+    Unsolvable,
+    /// Not known until the solutions are counted again
+    Unknown,
+}
+
+/// A puzzle being built or edited clue by clue, with its last known status and a solution kept
+/// so most edits can be judged without solving. Adding a clue that agrees with a unique solution
+/// keeps it unique, and one that doesn't leaves no solution at all. Removing a clue from a puzzle
+/// with several solutions leaves several. Other edits need a recount, which `status` does when
+/// asked.
+///
+/// The editor also keeps the solution of the clues alone for `reveal`, worked out the first time
+/// a cell is revealed and kept until the clues change.
+#[derive(Copy, Clone)]
+pub struct PuzzleEditor {
+    game: Game,
+    status: Status,
+    // A solution of the current puzzle, when one is known
+    solution: Option<[[u8; 9]; 9]>,
+    // The solution of the clues alone, or why there isn't one, once `reveal` has needed it
+    clue_solution: Option<Result<[[u8; 9]; 9], RevealError>>,
+}
+
+impl PuzzleEditor {
+    pub fn new(game: Game) -> Self {
+        PuzzleEditor {
+            game,
+            status: Status::Unknown,
+            solution: None,
+            clue_solution: None,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Fills in a cell, if `Game::can_place` allows it.
+    pub fn add_clue(&mut self, coord: Coord, cv: CellValue) -> Result<(), PlaceError> {
+        self.game.place(coord, cv)?;
+        self.game.givens[coord.0][coord.1] = true;
+        // Every solution now was a solution before, so the known one still counts if it agrees
+        // with the new clue.
+        let agrees = self
+            .solution
+            .is_some_and(|solution| solution[coord.0][coord.1] == cv as u8);
+        self.status = match self.status {
+            Status::Unique if agrees => Status::Unique,
+            Status::Unique | Status::Unsolvable => Status::Unsolvable,
+            _ => Status::Unknown,
+        };
+        if !agrees {
+            self.solution = None;
+        }
+        self.clue_solution = None;
+        Ok(())
+    }
+
+    /// Empties a cell that has a clue in it.
+    pub fn remove_clue(&mut self, coord: Coord) -> Result<(), EraseError> {
+        self.game = self.game.without_clue(coord)?;
+        // Every solution before is still a solution, and there may be new ones.
+        self.status = match self.status {
+            Status::Multiple => Status::Multiple,
+            _ => Status::Unknown,
+        };
+        self.clue_solution = None;
+        Ok(())
+    }
+
+    /// Fills in one cell with its value from the solution of the clues, the way `Game::reveal`
+    /// does, but only solving the first time. The solution, or the reason there isn't a single
+    /// one, is kept for later reveals until a clue is added or removed.
+    pub fn reveal(&mut self, coord: Coord) -> Result<Reveal, RevealError> {
+        self.game.check_revealable(coord)?;
+        let game = self.game;
+        let solution = (*self
+            .clue_solution
+            .get_or_insert_with(|| game.givens_solution()))?;
+        let reveal = self.game.reveal_from(coord, &solution);
+        // A right value in a puzzle with one solution keeps it at one, but anything else could
+        // have changed the count.
+        if self.status != Status::Unique {
+            self.status = Status::Unknown;
+        }
+        Ok(reveal)
+    }
+
+    /// The last known status, without solving anything. This is `Status::Unknown` when an edit
+    /// left it open.
+    pub fn cached_status(&self) -> Status {
+        self.status
+    }
+
+    /// Whether the puzzle has exactly one solution, counting the solutions only if the edits so
+    /// far didn't already settle it.
+    pub fn status(&mut self) -> Status {
+        if self.status == Status::Unknown {
+            let mut solutions = self.game.solutions();
+            self.solution = solutions.next().map(|solution| solution.to_array());
+            self.status = match (self.solution, solutions.next()) {
+                (None, _) => Status::Unsolvable,
+                (Some(_), None) => Status::Unique,
+                (Some(_), Some(_)) => Status::Multiple,
+            };
+        }
+        self.status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PuzzleEditor;
+    use crate::game::{CellValue, Game, RevealError};
+    use crate::gameboards::EASY;
+
+    #[test]
+    fn reveal_keeps_the_solution_until_the_clues_change() {
+        let mut solution = Game::new(EASY);
+        solution.solve().unwrap();
+        let mut editor = PuzzleEditor::new(Game::new(EASY));
+        assert!(editor.clue_solution.is_none());
+        let empty = (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| EASY[y][x] == 0)
+            .collect::<Vec<_>>();
+        let first = editor.reveal(empty[0]).unwrap();
+        assert_eq!(Some(first.value), solution.board[empty[0].0][empty[0].1]);
+        assert_eq!(editor.clue_solution, Some(Ok(solution.to_array())));
+        let second = editor.reveal(empty[1]).unwrap();
+        assert_eq!(Some(second.value), solution.board[empty[1].0][empty[1].1]);
+        let (y, x) = empty[2];
+        editor
+            .add_clue((y, x), solution.board[y][x].unwrap())
+            .unwrap();
+        assert!(editor.clue_solution.is_none());
+        assert_eq!(editor.reveal((y, x)), Err(RevealError::Given));
+    }
+
+    #[test]
+    fn reveal_keeps_errors_until_the_clues_change() {
+        let mut numbers = EASY;
+        let (y, x) = (0..81)
+            .map(Game::coords)
+            .find(|&(y, x)| EASY[y][x] != 0)
+            .unwrap();
+        let value = CellValue::new(numbers[y][x]).unwrap();
+        numbers[y][x] = 0;
+        for row in numbers.iter_mut().skip(3) {
+            *row = [0; 9];
+        }
+        let mut editor = PuzzleEditor::new(Game::new(numbers));
+        assert_eq!(editor.reveal((8, 8)), Err(RevealError::NotUnique));
+        assert_eq!(editor.clue_solution, Some(Err(RevealError::NotUnique)));
+        editor.add_clue((y, x), value).unwrap();
+        assert!(editor.clue_solution.is_none());
+    }
+}
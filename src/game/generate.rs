@@ -0,0 +1,200 @@
+// Making new puzzles: a random solution, with clues taken away one at a time for as long as the
+// puzzle stays unique, and for `generate_logical`, within reach of the logical techniques.
+
+#[cfg(feature = "rand")]
+use super::{BoardError, Solution};
+use super::{Difficulty, Game, SplitMix64, SudokuRng, Technique, TechniqueSet};
+use std::cell::Cell;
+
+// How many solutions `generate_logical` digs into before giving up on hitting the difficulty.
+const ATTEMPTS: usize = 64;
+
+// A random full board. The three 3x3s on the diagonal share no row or column, so any values at
+// all can go in them, and whatever they hold, the rest of the board can be filled in around them.
+fn random_solution(rng: &mut impl SudokuRng) -> Game {
+    let mut numbers = [[0; 9]; 9];
+    for sqr in 0..3 {
+        let mut values = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        for i in (1..9).rev() {
+            values.swap(i, rng.below(i + 1));
+        }
+        for (k, &value) in values.iter().enumerate() {
+            numbers[3 * sqr + k / 3][3 * sqr + k % 3] = value;
+        }
+    }
+    let mut game = Game::new(numbers);
+    game.solve()
+        .expect("the diagonal 3x3s can always be completed");
+    game
+}
+
+// Takes clues away from the board in a random order, putting back any whose removal leaves a game
+// that `keep` turns down.
+fn dig(numbers: &mut [[u8; 9]; 9], rng: &mut impl SudokuRng, keep: impl Fn(&Game) -> bool) {
+    let mut order = (0..81).collect::<Vec<_>>();
+    for i in (1..81).rev() {
+        order.swap(i, rng.below(i + 1));
+    }
+    for k in order {
+        let (y, x) = Game::coords(k);
+        let value = numbers[y][x];
+        if value == 0 {
+            continue;
+        }
+        numbers[y][x] = 0;
+        if !keep(&Game::new(*numbers)) {
+            numbers[y][x] = value;
+        }
+    }
+}
+
+impl Game {
+    /// Makes a puzzle with a unique solution that the logical techniques can finish without any
+    /// guessing, rated at exactly `target`. The same seed always gives the same puzzle. This is
+    /// `generate_logical_with_attempts` with 64 attempts, which is plenty for every difficulty
+    /// but `Hard`.
+    pub fn generate_logical(target: Difficulty, seed: u64) -> Option<Game> {
+        Game::generate_logical_with_attempts(target, seed, ATTEMPTS)
+    }
+
+    /// Like `generate_logical`, but with the number of attempts to make before giving up.
+    ///
+    /// Each attempt starts from a random solution and takes clues away in a random order, putting
+    /// back any whose removal leaves more than one solution or needs a technique harder than
+    /// `target`. Once the puzzle is rated at `target`, any removal that would make it easier is put
+    /// back too. If what's left isn't hard enough, it tries again from a new solution, and returns
+    /// `None` if none of the attempts land on `target`. `Guessing` always gives `None`.
+    ///
+    /// An attempt takes around 70ms in a release build. `Easy` and `Medium` almost always come out
+    /// on the first few. `Hard` puzzles are rare this way, since a board that needs triples usually
+    /// needs more than that: it takes around 150 attempts on average, so a budget of 500 or so
+    /// finds one for nearly every seed, in ten seconds or so. `Expert` and `Diabolical` take a
+    /// handful of attempts, but `Diabolical` ones are much slower, since the forcing chains get
+    /// tried after every removal, and usually take ten seconds or more in all.
+    pub fn generate_logical_with_attempts(
+        target: Difficulty,
+        seed: u64,
+        attempts: usize,
+    ) -> Option<Game> {
+        if target == Difficulty::Guessing {
+            return None;
+        }
+        // Techniques past the target are never needed, and leaving them out keeps the solver from
+        // trying the expensive ones on every board that's gotten too hard.
+        let techniques = Technique::ALL
+            .iter()
+            .copied()
+            .filter(|t| t.difficulty() <= target)
+            .collect::<TechniqueSet>();
+        let mut rng = SplitMix64::new(seed);
+        for _ in 0..attempts {
+            let mut numbers = random_solution(&mut rng).to_array();
+            let reached = Cell::new(false);
+            dig(&mut numbers, &mut rng, |game| {
+                if !game.solvable_with(&techniques) || game.count_solutions(2) != 1 {
+                    return false;
+                }
+                let on_target = game.difficulty() == target;
+                reached.set(reached.get() || on_target);
+                on_target || !reached.get()
+            });
+            let puzzle = Game::new(numbers);
+            if puzzle.difficulty() == target {
+                return Some(puzzle);
+            }
+        }
+        None
+    }
+
+    /// Takes clues away from a full board one at a time in a random order, as long as the puzzle
+    /// left keeps a unique solution, and returns how many clues are left when none more can go.
+    /// Every clue left is then needed, but a different order can leave fewer, so running this
+    /// with many seeds gives a picture of how low the count for a grid goes. Random orders usually
+    /// stop somewhere from 22 to 26, well above the 17 of the sparsest known puzzles like
+    /// `gameboards::SEVENTEEN`. Fails if `solved` has empty cells or breaks the rules. Only with
+    /// the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn min_unique_clues(
+        solved: &Game,
+        rng: &mut impl rand_core::RngCore,
+    ) -> Result<usize, BoardError> {
+        let mut numbers = Solution::new(*solved)?.into_game().to_array();
+        dig(&mut numbers, rng, |game| game.count_solutions(2) == 1);
+        Ok(numbers.iter().flatten().filter(|&&n| n != 0).count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Difficulty, Game, TechniqueSet};
+    #[cfg(feature = "rand")]
+    use crate::game::{SplitMix64, SudokuRng};
+    #[cfg(feature = "rand")]
+    use crate::gameboards;
+
+    fn assert_generated(target: Difficulty, seed: u64, attempts: usize) {
+        let game = Game::generate_logical_with_attempts(target, seed, attempts)
+            .unwrap_or_else(|| panic!("no {:?} puzzle for seed {}", target, seed));
+        assert_eq!(game.difficulty(), target, "seed {}", seed);
+        assert_eq!(game.count_solutions(2), 1, "seed {}", seed);
+        assert!(game.solvable_with(&TechniqueSet::all()), "seed {}", seed);
+    }
+
+    #[test]
+    fn easy_and_medium_come_out_for_fixed_seeds() {
+        for seed in 0..4 {
+            assert_generated(Difficulty::Easy, seed, 64);
+            assert_generated(Difficulty::Medium, seed, 64);
+        }
+        assert_eq!(
+            Game::generate_logical(Difficulty::Medium, 7).map(|game| game.to_line()),
+            Game::generate_logical(Difficulty::Medium, 7).map(|game| game.to_line())
+        );
+    }
+
+    #[test]
+    fn hard_comes_out_with_a_bigger_budget() {
+        assert_generated(Difficulty::Hard, 2, 500);
+    }
+
+    #[test]
+    fn gives_up_when_out_of_attempts() {
+        assert!(Game::generate_logical_with_attempts(Difficulty::Easy, 0, 0).is_none());
+        assert!(Game::generate_logical(Difficulty::Guessing, 0).is_none());
+    }
+
+    // A `rand_core` generator for the tests, since `rand_core` doesn't come with one
+    #[cfg(feature = "rand")]
+    struct Rng(SplitMix64);
+
+    #[cfg(feature = "rand")]
+    impl rand_core::RngCore for Rng {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn min_unique_clues_leaves_a_unique_puzzle() {
+        let mut solution = Game::new(gameboards::SEVENTEEN);
+        solution.solve().unwrap();
+        let mut rng = Rng(SplitMix64::new(200));
+        let clues = Game::min_unique_clues(&solution, &mut rng).unwrap();
+        assert!((17..=30).contains(&clues), "{} clues", clues);
+        assert!(Game::min_unique_clues(&Game::new(gameboards::SEVENTEEN), &mut rng).is_err());
+    }
+}
@@ -0,0 +1,1427 @@
+// Humanlike solving: the techniques a person would use to fill in cells or cross off possible
+// values, always reaching for the cheapest one that makes progress, with a log of each step taken.
+
+mod als;
+mod forcing;
+mod links;
+#[cfg(feature = "paranoid")]
+mod oracle;
+mod teaching;
+mod uniqueness;
+
+use super::{CellValue, Coord, Game, PlaceError};
+pub use forcing::{ChainLimits, Contradiction, ForcingChain};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+pub use teaching::{Hint, HintOutcome};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Technique {
+    /// The last empty cell in a row, column, or 3x3
+    FullHouse,
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    XWing,
+    /// Every empty cell but one has two candidates, and the last one has three. This assumes the
+    /// game has only one solution.
+    BugPlusOne,
+    /// Two rows, or two columns, with two places each for a value, where one place from each
+    /// shares a column, or row
+    Skyscraper,
+    /// A row and a column with two places each for a value, where one place from each shares a
+    /// 3x3
+    TwoStringKite,
+    /// Any other chain of two units with two places each for a value, linked by a pair of places
+    /// that see each other
+    TurbotFish,
+    /// A chain of cells that all have the same two candidates, where each sees the next
+    RemotePairs,
+    /// A chain of places for one value, alternating strong and weak links, that starts and ends
+    /// with a strong link, so one of its two ends holds the value
+    XChain,
+    Swordfish,
+    /// An X-wing with extra places for the value, all in one 3x3, so only cells that see them
+    /// lose it
+    FinnedXWing,
+    /// A swordfish with extra places for the value, all in one 3x3
+    FinnedSwordfish,
+    /// Two almost locked sets linked by a value that only one of them can hold
+    AlsXz,
+    /// Assuming a candidate and following singles from there until the board breaks
+    ForcingChain,
+    /// A forcing chain from any candidate in any cell, followed until the singles run out rather
+    /// than for a limited number of steps
+    Nishio,
+    /// A run of naked singles, each only there because of the ones before it, as grouped by
+    /// `Step::group_cascades`. The logical solver never takes this step itself, so it isn't in
+    /// `Technique::ALL`.
+    Cascade,
+}
+
+impl Technique {
+    /// Every technique the logical solver uses, from cheapest to most expensive. This is the order
+    /// the logical solver tries them in, and the order of `SolverPipeline::default`. Each
+    /// technique's name, cost, and the search that finds it all go by its place here.
+    pub const ALL: [Technique; 21] = [
+        Technique::FullHouse,
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::LockedCandidates,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::XWing,
+        Technique::BugPlusOne,
+        Technique::Skyscraper,
+        Technique::TwoStringKite,
+        Technique::TurbotFish,
+        Technique::RemotePairs,
+        Technique::XChain,
+        Technique::Swordfish,
+        Technique::FinnedXWing,
+        Technique::FinnedSwordfish,
+        Technique::AlsXz,
+        Technique::ForcingChain,
+        Technique::Nishio,
+    ];
+
+    // The name used for the technique in technique lists and replay files.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Technique::FullHouse => "full-house",
+            Technique::NakedSingle => "naked-single",
+            Technique::HiddenSingle => "hidden-single",
+            Technique::LockedCandidates => "locked-candidates",
+            Technique::NakedPair => "naked-pair",
+            Technique::HiddenPair => "hidden-pair",
+            Technique::NakedTriple => "naked-triple",
+            Technique::HiddenTriple => "hidden-triple",
+            Technique::XWing => "x-wing",
+            Technique::BugPlusOne => "bug-plus-one",
+            Technique::Skyscraper => "skyscraper",
+            Technique::TwoStringKite => "two-string-kite",
+            Technique::TurbotFish => "turbot-fish",
+            Technique::RemotePairs => "remote-pairs",
+            Technique::XChain => "x-chain",
+            Technique::Swordfish => "swordfish",
+            Technique::FinnedXWing => "finned-x-wing",
+            Technique::FinnedSwordfish => "finned-swordfish",
+            Technique::AlsXz => "als-xz",
+            Technique::ForcingChain => "forcing-chain",
+            Technique::Nishio => "nishio",
+            Technique::Cascade => "cascade",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Technique::ALL
+            .iter()
+            .chain(&[Technique::Cascade])
+            .copied()
+            .find(|t| t.name() == name)
+    }
+
+    /// How much a step with this technique adds to `Rating::score`: its place in `Technique::ALL`,
+    /// counting from 1. A cascade costs the same as the naked singles it's made of.
+    pub fn cost(self) -> usize {
+        match self {
+            Technique::Cascade => Technique::NakedSingle.cost(),
+            _ => self as usize + 1,
+        }
+    }
+
+    /// Whether the technique relies on the game having only one solution. These can make wrong
+    /// moves on a game with several, so the logical solver skips them unless its pipeline has
+    /// `SolverPipeline::assume_unique` set.
+    pub fn assumes_uniqueness(self) -> bool {
+        self == Technique::BugPlusOne
+    }
+
+    /// The difficulty tier a game needing this technique falls into.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::FullHouse
+            | Technique::NakedSingle
+            | Technique::HiddenSingle
+            | Technique::Cascade => Difficulty::Easy,
+            Technique::LockedCandidates | Technique::NakedPair | Technique::HiddenPair => {
+                Difficulty::Medium
+            }
+            Technique::NakedTriple | Technique::HiddenTriple => Difficulty::Hard,
+            Technique::XWing
+            | Technique::BugPlusOne
+            | Technique::Skyscraper
+            | Technique::TwoStringKite
+            | Technique::TurbotFish
+            | Technique::RemotePairs
+            | Technique::XChain
+            | Technique::Swordfish
+            | Technique::FinnedXWing
+            | Technique::FinnedSwordfish
+            | Technique::AlsXz => Difficulty::Expert,
+            Technique::ForcingChain | Technique::Nishio => Difficulty::Diabolical,
+        }
+    }
+}
+
+/// How hard a game is for a person, going by the hardest technique needed to solve it.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Difficulty {
+    /// Singles only
+    Easy,
+    /// Locked candidates and pairs
+    Medium,
+    /// Triples
+    Hard,
+    /// Fish, turbot fish, remote pairs, X-chains, BUG+1, and ALS-XZ
+    Expert,
+    /// Forcing chains and Nishio
+    Diabolical,
+    /// The logical techniques can't finish the game, so it needs guessing
+    Guessing,
+}
+
+/// A set of techniques, for limiting the logical solver to what a player knows.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TechniqueSet(u32);
+
+impl TechniqueSet {
+    pub fn empty() -> Self {
+        TechniqueSet(0)
+    }
+
+    pub fn all() -> Self {
+        Technique::ALL.iter().copied().collect()
+    }
+
+    pub fn insert(&mut self, technique: Technique) {
+        self.0 |= 1 << technique as u32;
+    }
+
+    pub fn contains(self, technique: Technique) -> bool {
+        self.0 & 1 << technique as u32 != 0
+    }
+
+    /// The techniques in the set, cheapest first.
+    pub fn iter(self) -> impl Iterator<Item = Technique> {
+        Technique::ALL
+            .iter()
+            .copied()
+            .filter(move |&t| self.contains(t))
+    }
+}
+
+impl std::iter::FromIterator<Technique> for TechniqueSet {
+    fn from_iter<I: IntoIterator<Item = Technique>>(iter: I) -> Self {
+        let mut set = TechniqueSet::empty();
+        for technique in iter {
+            set.insert(technique);
+        }
+        set
+    }
+}
+
+/// A name in a technique list that isn't a technique or group of techniques.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownTechnique(pub String);
+
+impl fmt::Display for UnknownTechnique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown technique {:?}", self.0)?;
+        match closest_name(&self.0) {
+            Some(name) => write!(f, ", did you mean {:?}?", name),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for UnknownTechnique {}
+
+impl fmt::Display for Technique {
+    /// Writes the technique's name as used in technique lists, like `x-wing`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Technique {
+    type Err = UnknownTechnique;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Technique::from_name(s.trim()).ok_or_else(|| UnknownTechnique(s.trim().to_string()))
+    }
+}
+
+const GROUP_NAMES: [&str; 8] = [
+    "singles",
+    "locked",
+    "pairs",
+    "triples",
+    "fish",
+    "turbot",
+    "uniqueness",
+    "all",
+];
+
+// The techniques a group name in a technique list stands for.
+fn group(name: &str) -> Option<&'static [Technique]> {
+    Some(match name {
+        "singles" => &[
+            Technique::FullHouse,
+            Technique::NakedSingle,
+            Technique::HiddenSingle,
+        ],
+        "locked" => &[Technique::LockedCandidates],
+        "pairs" => &[Technique::NakedPair, Technique::HiddenPair],
+        "triples" => &[Technique::NakedTriple, Technique::HiddenTriple],
+        "fish" => &[
+            Technique::XWing,
+            Technique::Swordfish,
+            Technique::FinnedXWing,
+            Technique::FinnedSwordfish,
+        ],
+        "turbot" => &[
+            Technique::Skyscraper,
+            Technique::TwoStringKite,
+            Technique::TurbotFish,
+        ],
+        "uniqueness" => &[Technique::BugPlusOne],
+        "all" => &Technique::ALL,
+        _ => return None,
+    })
+}
+
+// The technique or group name closest to a misspelled one, if any is close enough to be a likely
+// typo.
+fn closest_name(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    Technique::ALL
+        .iter()
+        .map(|t| t.name())
+        .chain(GROUP_NAMES.iter().copied())
+        .map(|candidate| (edit_distance(&name, candidate), candidate))
+        .filter(|&(distance, candidate)| distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// The number of single character insertions, deletions, and substitutions to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substituted = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// The techniques in a comma-separated list of technique and group names, in the order they're
+// listed, with each group's techniques cheapest first.
+fn parse_names(s: &str) -> Result<Vec<Technique>, UnknownTechnique> {
+    let mut techniques = Vec::new();
+    for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        match group(name) {
+            Some(group) => techniques.extend_from_slice(group),
+            None => techniques.push(name.parse()?),
+        }
+    }
+    Ok(techniques)
+}
+
+impl FromStr for TechniqueSet {
+    type Err = UnknownTechnique;
+
+    /// Reads a comma-separated list of techniques, like `naked-single,x-wing`. The groups
+    /// `singles`, `locked`, `pairs`, `triples`, `fish`, `turbot`, `uniqueness`, and `all` can be
+    /// used as shorthand.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parse_names(s)?.into_iter().collect())
+    }
+}
+
+impl Extend<Technique> for TechniqueSet {
+    fn extend<I: IntoIterator<Item = Technique>>(&mut self, iter: I) {
+        for technique in iter {
+            self.insert(technique);
+        }
+    }
+}
+
+/// The techniques the logical solver tries, in order. After every step it goes back to the first
+/// one, so earlier techniques are always preferred. The default is every technique, cheapest
+/// first, with the ones that assume the game has only one solution skipped.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SolverPipeline {
+    techniques: Vec<Technique>,
+    assume_unique: bool,
+}
+
+impl SolverPipeline {
+    /// Reads a comma-separated list of technique names, like `x-wing,naked-single`, keeping the
+    /// order given. The group names `TechniqueSet` takes work here too, standing for their
+    /// techniques cheapest first. A technique listed more than once is only tried at its first
+    /// place. A name that isn't a technique or group is an error, with the closest name
+    /// suggested if there's a likely one.
+    pub fn from_names(names: &str) -> Result<Self, UnknownTechnique> {
+        let mut techniques = Vec::new();
+        for technique in parse_names(names)? {
+            if !techniques.contains(&technique) {
+                techniques.push(technique);
+            }
+        }
+        Ok(SolverPipeline::new(techniques))
+    }
+
+    fn new(techniques: Vec<Technique>) -> Self {
+        SolverPipeline {
+            techniques,
+            assume_unique: false,
+        }
+    }
+
+    /// Lets the techniques that rely on the game having only one solution, like BUG+1, run when
+    /// their turn comes. They're skipped otherwise, even if they're listed, since on a game with
+    /// several solutions they can fill in wrong values. This isn't part of the names the pipeline
+    /// is written as.
+    pub fn assume_unique(mut self, assume_unique: bool) -> Self {
+        self.assume_unique = assume_unique;
+        self
+    }
+
+    /// The techniques, in the order they're tried.
+    pub fn techniques(&self) -> &[Technique] {
+        &self.techniques
+    }
+}
+
+impl Default for SolverPipeline {
+    fn default() -> Self {
+        SolverPipeline::new(Technique::ALL.to_vec())
+    }
+}
+
+impl From<TechniqueSet> for SolverPipeline {
+    /// The techniques in the set, cheapest first.
+    fn from(set: TechniqueSet) -> Self {
+        SolverPipeline::new(set.iter().collect())
+    }
+}
+
+impl fmt::Display for SolverPipeline {
+    /// Writes the technique names separated by commas, the way `from_names` reads them.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, technique) in self.techniques.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", technique)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SolverPipeline {
+    type Err = UnknownTechnique;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SolverPipeline::from_names(s)
+    }
+}
+
+/// A fuller rating than `Game::difficulty`, from `Game::rate`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// The total cost of every step taken, where a step costs its technique's position in
+    /// `Technique::ALL`, counting from 1. Harder and longer solves score higher.
+    pub score: usize,
+    /// The most expensive technique used, or `None` if no steps were needed
+    pub hardest_technique: Option<Technique>,
+    /// How many steps each technique used took, cheapest first
+    pub technique_counts: Vec<(Technique, usize)>,
+}
+
+/// The outcome of solving with a limited set of techniques, from `Game::restricted_solve`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RestrictedSolve {
+    pub solved: bool,
+    /// Cells still empty when the allowed techniques ran out
+    pub remaining: usize,
+    /// The cheapest technique outside the set that could have made progress where the allowed
+    /// techniques got stuck
+    pub unblocked_by: Option<Technique>,
+}
+
+/// A single deduction made by the logical solver: the cells it filled in and the possible values
+/// it crossed off, as `(row, col, value)`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub technique: Technique,
+    pub placements: Vec<(usize, usize, CellValue)>,
+    pub eliminations: Vec<(usize, usize, CellValue)>,
+    /// For a forcing chain, the reasoning behind it
+    pub chain: Option<Box<ForcingChain>>,
+}
+
+impl Step {
+    fn placement(technique: Technique, row: usize, col: usize, i: usize) -> Self {
+        Step {
+            technique,
+            placements: vec![(row, col, CellValue::from_index(i))],
+            eliminations: Vec::new(),
+            chain: None,
+        }
+    }
+
+    fn eliminations(technique: Technique, eliminations: Vec<(usize, usize, CellValue)>) -> Self {
+        Step {
+            technique,
+            placements: Vec::new(),
+            eliminations,
+            chain: None,
+        }
+    }
+
+    /// Merges each run of naked singles where every placement after the first sees an earlier
+    /// one, so that it only became a naked single because of the run, into a single
+    /// `Technique::Cascade` step with all of the run's placements in order. A naked single that
+    /// doesn't start a run stays as it is, as does every other step. This makes walkthroughs of
+    /// easy games read more like how people solve them.
+    pub fn group_cascades(steps: Vec<Step>) -> Vec<Step> {
+        let mut grouped: Vec<Step> = Vec::with_capacity(steps.len());
+        for step in steps {
+            if let (Some(last), [(y, x, _)]) = (grouped.last_mut(), &step.placements[..]) {
+                let continues = step.technique == Technique::NakedSingle
+                    && (last.technique == Technique::NakedSingle
+                        || last.technique == Technique::Cascade)
+                    && last
+                        .placements
+                        .iter()
+                        .any(|&(py, px, _)| Game::are_peers((py, px), (*y, *x)));
+                if continues {
+                    last.technique = Technique::Cascade;
+                    last.placements.extend(step.placements);
+                    continue;
+                }
+            }
+            grouped.push(step);
+        }
+        grouped
+    }
+}
+
+// Units are numbered with rows as 0-8, columns as 9-17, and 3x3s as 18-26.
+pub(super) fn unit_cells(unit: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..9).map(move |i| match unit / 9 {
+        0 => (unit, i),
+        1 => (i, unit - 9),
+        _ => {
+            let s = unit - 18;
+            (3 * (s / 3) + i / 3, 3 * (s % 3) + i % 3)
+        }
+    })
+}
+
+// All the ways to pick `n` of the first `len` items, as bitmasks.
+fn combinations(len: usize, n: usize) -> impl Iterator<Item = u16> {
+    (0..1u16 << len).filter(move |m| m.count_ones() as usize == n)
+}
+
+fn mask_values(mask: u16) -> impl Iterator<Item = usize> {
+    (0..9).filter(move |&i| mask & 1 << i != 0)
+}
+
+impl Game {
+    /// Solves as much of the game as possible using only logical techniques, without any
+    /// guessing, and returns every step taken in order.
+    pub fn solve_logical(&mut self) -> Vec<Step> {
+        self.solve_logical_with(&Technique::ALL, false)
+    }
+
+    /// Solves as much of the game as possible with the pipeline's techniques, trying them in its
+    /// order, and returns every step taken.
+    pub fn solve_with_pipeline(&mut self, pipeline: &SolverPipeline) -> Vec<Step> {
+        self.solve_logical_with(pipeline.techniques(), pipeline.assume_unique)
+    }
+
+    /// Applies logical techniques until the board is full or the next move would have to be a
+    /// guess, and returns whether the board is full. What's left on an unfinished board is exactly
+    /// where deduction gives out and searching would have to take over.
+    pub fn solve_until_guess(&mut self) -> bool {
+        self.solve_logical();
+        self.solved()
+    }
+
+    /// Shows the ripple effect of a move: places the value on a copy of the game, fills in
+    /// everything that follows from singles alone, and returns each cell that got filled in as a
+    /// result.
+    pub fn cascade(&self, row: usize, col: usize, cv: CellValue) -> Vec<(usize, usize, CellValue)> {
+        let mut after = self.what_if();
+        after.unset_cell(row, col);
+        after.set_cell(row, col, cv);
+        after.solve_logical_with(
+            &[
+                Technique::FullHouse,
+                Technique::NakedSingle,
+                Technique::HiddenSingle,
+            ],
+            false,
+        );
+        after
+            .iter_cells()
+            .filter(|&(y, x, _)| (y, x) != (row, col) && self.board[y][x].is_none())
+            .filter_map(|(y, x, &cell)| cell.map(|cv| (y, x, cv)))
+            .collect()
+    }
+
+    /// Everything that follows logically from a move: places the value on a copy of the game, the
+    /// same way `place` would, then uses every technique until none of them can make progress.
+    /// Unlike `cascade`, the copy keeps the candidates the techniques crossed off along the way.
+    pub fn logical_closure(
+        &self,
+        row: usize,
+        col: usize,
+        cv: CellValue,
+    ) -> Result<Game, PlaceError> {
+        let mut after = self.what_if();
+        after.place((row, col), cv)?;
+        after.solve_logical();
+        Ok(after)
+    }
+
+    /// Like `cascade`, but with the cells that follow listed in the order singles fill them in,
+    /// each one forced by the placements before it. Returns `None` if the cell is filled or can't
+    /// take the value.
+    pub fn longest_forced_chain_from(
+        &self,
+        coord: Coord,
+        cv: CellValue,
+    ) -> Option<Vec<(usize, usize, CellValue)>> {
+        let singles = [
+            Technique::FullHouse,
+            Technique::NakedSingle,
+            Technique::HiddenSingle,
+        ];
+        self.longest_forced_chain_with(coord, cv, &singles.iter().copied().collect())
+    }
+
+    /// Like `longest_forced_chain_from`, but following the given techniques instead of only
+    /// singles. Only the cells they fill in are listed, not the candidates they cross off.
+    pub fn longest_forced_chain_with(
+        &self,
+        (row, col): Coord,
+        cv: CellValue,
+        techniques: &TechniqueSet,
+    ) -> Option<Vec<(usize, usize, CellValue)>> {
+        if row >= 9 || col >= 9 || self.poss_mask(row, col) & 1 << usize::from(cv) == 0 {
+            return None;
+        }
+        let mut after = self.what_if();
+        after.set_cell(row, col, cv);
+        let techniques = techniques.iter().collect::<Vec<_>>();
+        let steps = after.solve_logical_with(&techniques, false);
+        Some(steps.into_iter().flat_map(|step| step.placements).collect())
+    }
+
+    /// Ranks every candidate of every empty cell by how many cells placing it would fill in
+    /// through singles, longest first, and returns the first `n` with the length of each one's
+    /// chain. Ties keep board order. A wrong candidate can set off a chain too, so this says
+    /// nothing about which moves are right.
+    pub fn most_productive_moves(&self, n: usize) -> Vec<(usize, usize, CellValue, usize)> {
+        let mut moves = self
+            .all_valid_moves()
+            .into_iter()
+            .flat_map(|(y, x, values)| values.into_iter().map(move |cv| (y, x, cv)))
+            .filter_map(|(y, x, cv)| {
+                let chain = self.longest_forced_chain_from((y, x), cv)?;
+                Some((y, x, cv, chain.len()))
+            })
+            .collect::<Vec<_>>();
+        moves.sort_by_key(|&(_, _, _, len)| std::cmp::Reverse(len));
+        moves.truncate(n);
+        moves
+    }
+
+    /// Lists every technique used to solve a copy of the game logically, not just the hardest.
+    pub fn required_techniques(&self) -> HashSet<Technique> {
+        let mut game = *self;
+        game.solve_logical()
+            .into_iter()
+            .map(|step| step.technique)
+            .collect()
+    }
+
+    /// Counts the cells each technique filled in while solving a copy of the game logically. Every
+    /// technique used is listed, cheapest first, so the ones that only cross off values show up
+    /// with a count of 0.
+    pub fn technique_contribution(&self) -> Vec<(Technique, usize)> {
+        let mut game = *self;
+        let steps = game.solve_logical();
+        Technique::ALL
+            .iter()
+            .copied()
+            .filter(|&t| steps.iter().any(|step| step.technique == t))
+            .map(|t| {
+                let placed = steps
+                    .iter()
+                    .filter(|step| step.technique == t)
+                    .map(|step| step.placements.len())
+                    .sum();
+                (t, placed)
+            })
+            .collect()
+    }
+
+    /// Rates the game by the hardest technique needed to solve it logically.
+    pub fn difficulty(&self) -> Difficulty {
+        self.rate().difficulty
+    }
+
+    /// Rates the game by solving a copy of it logically, with the difficulty tier along with
+    /// what went into it.
+    pub fn rate(&self) -> Rating {
+        let mut game = *self;
+        let steps = game.solve_logical();
+        let hardest_technique = steps.iter().map(|step| step.technique).max();
+        let difficulty = if game.solved() {
+            hardest_technique.map_or(Difficulty::Easy, Technique::difficulty)
+        } else {
+            Difficulty::Guessing
+        };
+        let technique_counts = Technique::ALL
+            .iter()
+            .map(|&t| (t, steps.iter().filter(|step| step.technique == t).count()))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        let score = steps.iter().map(|step| step.technique.cost()).sum();
+        Rating {
+            difficulty,
+            score,
+            hardest_technique,
+            technique_counts,
+        }
+    }
+
+    /// Shows what one use of a technique would do, without changing the game: the cells it would
+    /// fill in and the candidates it would cross off, or `None` if it can't make progress.
+    pub fn preview_technique(&self, technique: Technique) -> Option<Step> {
+        self.find_step(technique)
+    }
+
+    /// Uses a technique once and returns what it did, or `None` if it couldn't make progress. This
+    /// makes the same move `preview_technique` shows.
+    pub fn apply_technique(&mut self, technique: Technique) -> Option<Step> {
+        let step = self.find_step(technique)?;
+        self.apply_step(&step);
+        Some(step)
+    }
+
+    /// Checks whether the logical techniques can finish the game without any guessing, which is
+    /// what most players expect of a fair puzzle.
+    pub fn is_logically_solvable(&self) -> bool {
+        let mut game = *self;
+        game.solve_logical();
+        game.solved()
+    }
+
+    /// Checks whether the game can be solved using only the given techniques.
+    pub fn solvable_with(&self, techniques: &TechniqueSet) -> bool {
+        self.restricted_solve(techniques).solved
+    }
+
+    /// Solves a copy of the game using only the given techniques, and if they aren't enough,
+    /// reports how far they got and which missing technique would have helped.
+    pub fn restricted_solve(&self, techniques: &TechniqueSet) -> RestrictedSolve {
+        let mut game = *self;
+        game.solve_logical_with(&techniques.iter().collect::<Vec<_>>(), false);
+        let solved = game.solved();
+        RestrictedSolve {
+            solved,
+            remaining: game
+                .iter_cells()
+                .filter(|&(_, _, cell)| cell.is_none())
+                .count(),
+            unblocked_by: if solved {
+                None
+            } else {
+                Technique::ALL
+                    .iter()
+                    .copied()
+                    .filter(|&t| !techniques.contains(t) && !t.assumes_uniqueness())
+                    .find(|&t| game.find_step(t).is_some())
+            },
+        }
+    }
+
+    /// Finds the fewest guesses needed to solve the game, where a guess is filling in a cell that
+    /// the logical techniques can't, and the logical solver is run to exhaustion between guesses.
+    /// Guesses are assumed to be lucky, so this is a lower bound on what any solver must guess.
+    /// This searches every combination of guessed cells, so it gets expensive quickly for games
+    /// that need more than a couple of guesses.
+    ///
+    /// Panics if the game has no solution.
+    pub fn min_guesses(&self) -> usize {
+        let mut solution = *self;
+        solution.solve().expect("game has no solution");
+        (0..)
+            .find(|&guesses| self.solvable_with_guesses(&solution, guesses))
+            .unwrap()
+    }
+
+    fn solvable_with_guesses(&self, solution: &Game, guesses: usize) -> bool {
+        let mut game = *self;
+        game.solve_logical();
+        if game.solved() {
+            return true;
+        }
+        guesses > 0
+            && game
+                .iter_cells()
+                .filter(|&(_, _, cell)| cell.is_none())
+                .any(|(y, x, _)| {
+                    let mut guessed = game;
+                    guessed.set_cell(y, x, solution.board[y][x].unwrap());
+                    guessed.solvable_with_guesses(solution, guesses - 1)
+                })
+    }
+
+    // A copy of the game for trying out a move that may be wrong. What the techniques find after a
+    // wrong move still follows from it, so the oracle has no say over the copy.
+    fn what_if(&self) -> Game {
+        #[cfg(feature = "paranoid")]
+        let game = Game {
+            oracle: None,
+            ..*self
+        };
+        #[cfg(not(feature = "paranoid"))]
+        let game = *self;
+        game
+    }
+
+    // Techniques that assume the game has only one solution are skipped unless `assume_unique` is
+    // set.
+    fn solve_logical_with(&mut self, techniques: &[Technique], assume_unique: bool) -> Vec<Step> {
+        let techniques = techniques
+            .iter()
+            .copied()
+            .filter(|t| assume_unique || !t.assumes_uniqueness())
+            .collect::<Vec<_>>();
+        let mut steps = Vec::new();
+        // Always start over from the cheapest technique after making progress, the way a person
+        // would look for the easy deductions the last step opened up.
+        while !self.solved() && self.is_valid(false) {
+            match techniques.iter().find_map(|&t| self.find_step(t)) {
+                Some(step) => {
+                    self.apply_step(&step);
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+        steps
+    }
+
+    fn find_step(&self, technique: Technique) -> Option<Step> {
+        let step = match technique {
+            Technique::FullHouse => self.find_full_house(),
+            Technique::NakedSingle => self.find_naked_single(),
+            Technique::HiddenSingle => self.find_hidden_single(),
+            Technique::LockedCandidates => self.find_locked_candidates(),
+            Technique::NakedPair => self.find_naked_subset(technique, 2),
+            Technique::HiddenPair => self.find_hidden_subset(technique, 2),
+            Technique::NakedTriple => self.find_naked_subset(technique, 3),
+            Technique::HiddenTriple => self.find_hidden_subset(technique, 3),
+            Technique::XWing => self.find_fish(technique, 2, false),
+            Technique::BugPlusOne => self.find_bug_plus_one(),
+            Technique::Skyscraper | Technique::TwoStringKite | Technique::TurbotFish => {
+                self.find_turbot_fish(technique)
+            }
+            Technique::RemotePairs => self.find_remote_pairs(),
+            Technique::XChain => self.find_x_chain(),
+            Technique::Swordfish => self.find_fish(technique, 3, false),
+            Technique::FinnedXWing => self.find_fish(technique, 2, true),
+            Technique::FinnedSwordfish => self.find_fish(technique, 3, true),
+            Technique::AlsXz => self.find_als_xz(),
+            Technique::ForcingChain => self.find_forcing_chain(ChainLimits::default()),
+            Technique::Nishio => self.find_nishio(),
+            Technique::Cascade => None,
+        };
+        #[cfg(feature = "paranoid")]
+        if let Some(step) = &step {
+            self.check_oracle(step);
+        }
+        step
+    }
+
+    fn apply_step(&mut self, step: &Step) {
+        for &(y, x, cv) in &step.placements {
+            self.set_cell(y, x, cv);
+        }
+        for &(y, x, cv) in &step.eliminations {
+            self.eliminate(y, x, cv);
+        }
+    }
+
+    // Applies a step that came from somewhere other than the logical solver, like a saved replay,
+    // if it makes sense from the current state: every cell it touches has to be empty and still
+    // have the value as a candidate, and it can't rule out every solution. Returns whether the
+    // step was applied.
+    pub(crate) fn try_apply_step(&mut self, step: &Step) -> bool {
+        let touches_candidates = step
+            .placements
+            .iter()
+            .chain(&step.eliminations)
+            .all(|&(y, x, cv)| y < 9 && x < 9 && self.poss_mask(y, x) & 1 << usize::from(cv) != 0);
+        if !touches_candidates {
+            return false;
+        }
+        let mut after = *self;
+        after.apply_step(step);
+        if !after.is_valid(false) || (after.count_solutions(1) == 0 && self.count_solutions(1) > 0)
+        {
+            return false;
+        }
+        *self = after;
+        true
+    }
+
+    // The possible values for an empty cell as a bitmask, or 0 for a filled cell.
+    fn poss_mask(&self, row: usize, col: usize) -> u16 {
+        if self.board[row][col].is_some() {
+            return 0;
+        }
+        self.cell_poss[row][col]
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p)
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    // The empty cells in a unit that can still take the value at index `i`.
+    fn unit_positions(&self, unit: usize, i: usize) -> Vec<(usize, usize)> {
+        self.digit_positions(unit, i).cells(unit).collect()
+    }
+
+    fn find_full_house(&self) -> Option<Step> {
+        for unit in 0..27 {
+            let mut empty = unit_cells(unit).filter(|&(y, x)| self.board[y][x].is_none());
+            if let (Some((y, x)), None) = (empty.next(), empty.next()) {
+                let present = unit_cells(unit)
+                    .filter_map(|(y, x)| self.board[y][x])
+                    .fold(0u16, |mask, cv| mask | 1 << usize::from(cv));
+                let missing = !present & 0x1ff;
+                // In a broken state, the missing value may already be ruled out for the cell.
+                if missing.count_ones() == 1 && self.poss_mask(y, x) & missing != 0 {
+                    let i = missing.trailing_zeros() as usize;
+                    return Some(Step::placement(Technique::FullHouse, y, x, i));
+                }
+            }
+        }
+        None
+    }
+
+    fn find_naked_single(&self) -> Option<Step> {
+        self.iter()
+            .map(|(y, x, _, _)| (y, x, self.poss_mask(y, x)))
+            .find(|&(_, _, mask)| mask.count_ones() == 1)
+            .map(|(y, x, mask)| {
+                let i = mask.trailing_zeros() as usize;
+                Step::placement(Technique::NakedSingle, y, x, i)
+            })
+    }
+
+    fn find_hidden_single(&self) -> Option<Step> {
+        for unit in 0..27 {
+            for i in 0..9 {
+                if let [(y, x)] = self.unit_positions(unit, i)[..] {
+                    return Some(Step::placement(Technique::HiddenSingle, y, x, i));
+                }
+            }
+        }
+        None
+    }
+
+    fn find_locked_candidates(&self) -> Option<Step> {
+        for unit in 0..27 {
+            for i in 0..9 {
+                let cells = self.unit_positions(unit, i);
+                let (y0, x0) = match cells.first() {
+                    Some(&cell) => cell,
+                    None => continue,
+                };
+                // Pointing: the value is confined to one row or column of a 3x3, so it can't go
+                // anywhere else in that row or column. Claiming: the value is confined to one 3x3
+                // within a row or column, so it can't go anywhere else in that 3x3.
+                let mut targets = Vec::new();
+                if unit >= 18 {
+                    if cells.iter().all(|&(y, _)| y == y0) {
+                        targets.push(y0);
+                    }
+                    if cells.iter().all(|&(_, x)| x == x0) {
+                        targets.push(9 + x0);
+                    }
+                } else if cells
+                    .iter()
+                    .all(|&(y, x)| self.sqrs_ind(y, x) == self.sqrs_ind(y0, x0))
+                {
+                    targets.push(18 + self.sqrs_ind(y0, x0));
+                }
+                for target in targets {
+                    let eliminations = self
+                        .unit_positions(target, i)
+                        .into_iter()
+                        .filter(|cell| !cells.contains(cell))
+                        .map(|(y, x)| (y, x, CellValue::from_index(i)))
+                        .collect::<Vec<_>>();
+                    if !eliminations.is_empty() {
+                        return Some(Step::eliminations(
+                            Technique::LockedCandidates,
+                            eliminations,
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_naked_subset(&self, technique: Technique, n: usize) -> Option<Step> {
+        for unit in 0..27 {
+            let empty = unit_cells(unit)
+                .map(|(y, x)| (y, x, self.poss_mask(y, x)))
+                .filter(|&(_, _, mask)| mask != 0)
+                .collect::<Vec<_>>();
+            // `n` cells that only have `n` values between them must take exactly those values, so
+            // no other cell in the unit can.
+            for chosen in combinations(empty.len(), n) {
+                let values = empty
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| chosen & 1 << j != 0)
+                    .fold(0, |acc, (_, &(_, _, mask))| acc | mask);
+                if values.count_ones() as usize != n {
+                    continue;
+                }
+                let eliminations = empty
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| chosen & 1 << j == 0)
+                    .flat_map(|(_, &(y, x, mask))| {
+                        mask_values(mask & values).map(move |i| (y, x, CellValue::from_index(i)))
+                    })
+                    .collect::<Vec<_>>();
+                if !eliminations.is_empty() {
+                    return Some(Step::eliminations(technique, eliminations));
+                }
+            }
+        }
+        None
+    }
+
+    fn find_hidden_subset(&self, technique: Technique, n: usize) -> Option<Step> {
+        for unit in 0..27 {
+            let cells = unit_cells(unit).collect::<Vec<_>>();
+            // For each value still missing from the unit, the positions it can take as a bitmask
+            let missing = (0..9)
+                .map(|i| (i, self.digit_positions(unit, i).bits()))
+                .filter(|&(_, positions)| positions != 0)
+                .collect::<Vec<_>>();
+            // `n` values that only have `n` cells between them must take exactly those cells, so
+            // those cells can't take any other value.
+            for chosen in combinations(missing.len(), n) {
+                let (values, positions) = missing
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| chosen & 1 << j != 0)
+                    .fold((0u16, 0u16), |(v, p), (_, &(i, positions))| {
+                        (v | 1 << i, p | positions)
+                    });
+                if positions.count_ones() as usize != n {
+                    continue;
+                }
+                let eliminations = mask_values(positions)
+                    .map(|j| cells[j])
+                    .flat_map(|(y, x)| {
+                        mask_values(self.poss_mask(y, x) & !values)
+                            .map(move |i| (y, x, CellValue::from_index(i)))
+                    })
+                    .collect::<Vec<_>>();
+                if !eliminations.is_empty() {
+                    return Some(Step::eliminations(technique, eliminations));
+                }
+            }
+        }
+        None
+    }
+
+    // With `finned`, the base lines may also have extra places for the value outside the cover
+    // lines, as long as those fins all share a 3x3. Either a fin holds the value or the fish works
+    // as usual, so a cell can only lose the value when the fish would clear it and it sees every
+    // fin. Sashimi fish, where a base line has a single place in the cover lines besides its fins,
+    // are included.
+    fn find_fish(&self, technique: Technique, n: usize, finned: bool) -> Option<Step> {
+        for i in 0..9 {
+            // Try rows as the base lines with columns as the cover lines, then the other way round.
+            for &(base, cover) in &[(0, 9), (9, 0)] {
+                let most = if finned { 9 } else { n };
+                let lines = (0..9)
+                    .map(|line| (line, self.digit_positions(base + line, i).bits()))
+                    .filter(|&(_, positions)| {
+                        (2..=most).contains(&(positions.count_ones() as usize))
+                    })
+                    .collect::<Vec<_>>();
+                // If the value is confined to `n` cover lines across `n` base lines, then each
+                // cover line has its instance of the value in one of the base lines, so it can't
+                // go anywhere else in the cover lines.
+                for chosen in combinations(lines.len(), n) {
+                    let chosen = mask_values(chosen).map(|j| lines[j]).collect::<Vec<_>>();
+                    let bases = chosen.iter().fold(0u16, |acc, &(line, _)| acc | 1 << line);
+                    let all = chosen
+                        .iter()
+                        .fold(0u16, |acc, &(_, positions)| acc | positions);
+                    let cover_options = if finned {
+                        combinations(9, n).filter(|&c| c & !all == 0).collect()
+                    } else if all.count_ones() as usize == n {
+                        vec![all]
+                    } else {
+                        continue;
+                    };
+                    for covers in cover_options {
+                        // Every base line needs a place in the cover lines to be part of the fish.
+                        if chosen.iter().any(|&(_, positions)| positions & covers == 0) {
+                            continue;
+                        }
+                        let fins = chosen
+                            .iter()
+                            .flat_map(|&(line, positions)| {
+                                mask_values(positions & !covers)
+                                    .map(move |j| unit_cells(base + line).nth(j).unwrap())
+                            })
+                            .collect::<Vec<_>>();
+                        if finned == fins.is_empty() {
+                            continue;
+                        }
+                        let fin_box = fins.first().map(|&(y, x)| (y / 3, x / 3));
+                        if fins.iter().any(|&(y, x)| Some((y / 3, x / 3)) != fin_box) {
+                            continue;
+                        }
+                        let eliminations = mask_values(covers)
+                            .flat_map(|line| unit_cells(cover + line).enumerate())
+                            .filter(|&(j, (y, x))| {
+                                bases & 1 << j == 0
+                                    && self.poss_mask(y, x) & 1 << i != 0
+                                    && fin_box.map_or(true, |b| b == (y / 3, x / 3))
+                            })
+                            .map(|(_, (y, x))| (y, x, CellValue::from_index(i)))
+                            .collect::<Vec<_>>();
+                        if !eliminations.is_empty() {
+                            return Some(Step::eliminations(technique, eliminations));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Makes one elimination with an X-wing (`size` 2) or swordfish (`size` 3), finned or sashimi
+    /// ones included when `finned` is set. Returns whether anything was eliminated.
+    pub fn apply_fish(&mut self, size: usize, finned: bool) -> bool {
+        let technique = match (size, finned) {
+            (2, false) => Technique::XWing,
+            (3, false) => Technique::Swordfish,
+            (2, true) => Technique::FinnedXWing,
+            (3, true) => Technique::FinnedSwordfish,
+            _ => return false,
+        };
+        self.apply_technique(technique).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unit_cells, SolverPipeline, Step, Technique, TechniqueSet};
+    use crate::game::{CellValue, Coord, Game};
+    use crate::gameboards;
+
+    // An empty board with 1 crossed off everywhere in each row but the given columns.
+    fn ones_only_in(rows: &[(usize, &[usize])]) -> Game {
+        let mut game = Game::new(gameboards::ZEROS);
+        for &(row, keep) in rows {
+            for (y, x) in unit_cells(row).filter(|&(_, x)| !keep.contains(&x)) {
+                game.eliminate_candidate((y, x), CellValue::One).unwrap();
+            }
+        }
+        game
+    }
+
+    fn fish_eliminations(game: &Game, size: usize) -> Vec<Coord> {
+        let step = game.find_fish(Technique::FinnedXWing, size, true).unwrap();
+        assert!(step.placements.is_empty());
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|&(_, _, cv)| cv == CellValue::One));
+        step.eliminations.iter().map(|&(y, x, _)| (y, x)).collect()
+    }
+
+    #[test]
+    fn techniques_round_trip_through_their_names() {
+        for &technique in &Technique::ALL {
+            let name = technique.to_string();
+            assert_eq!(name.parse::<Technique>(), Ok(technique), "{}", name);
+            assert_eq!(Technique::from_name(&name), Some(technique));
+        }
+        let pipeline = SolverPipeline::default();
+        assert_eq!(pipeline.to_string().parse(), Ok(pipeline));
+    }
+
+    #[test]
+    fn cascades_round_trip_through_their_name() {
+        assert_eq!("cascade".parse(), Ok(Technique::Cascade));
+        assert!(!Technique::ALL.contains(&Technique::Cascade));
+    }
+
+    #[test]
+    fn default_pipeline_is_cheapest_first() {
+        let pipeline = SolverPipeline::default();
+        assert_eq!(pipeline.techniques(), Technique::ALL);
+        for pair in pipeline.techniques().windows(2) {
+            assert!(pair[0].cost() < pair[1].cost(), "{:?}", pair);
+            assert!(pair[0].difficulty() <= pair[1].difficulty(), "{:?}", pair);
+        }
+        assert_eq!(SolverPipeline::from(TechniqueSet::all()), pipeline);
+    }
+
+    #[test]
+    fn from_names_keeps_the_order_given() {
+        let pipeline = SolverPipeline::from_names("x-wing, singles,naked-single,pairs").unwrap();
+        assert_eq!(
+            pipeline.techniques(),
+            [
+                Technique::XWing,
+                Technique::FullHouse,
+                Technique::NakedSingle,
+                Technique::HiddenSingle,
+                Technique::NakedPair,
+                Technique::HiddenPair,
+            ]
+        );
+        assert_eq!(
+            pipeline.to_string(),
+            "x-wing,full-house,naked-single,hidden-single,naked-pair,hidden-pair"
+        );
+    }
+
+    #[test]
+    fn unknown_names_suggest_the_closest() {
+        let error = SolverPipeline::from_names("naked-single,x-wnig").unwrap_err();
+        assert_eq!(error.0, "x-wnig");
+        assert_eq!(
+            error.to_string(),
+            "unknown technique \"x-wnig\", did you mean \"x-wing\"?"
+        );
+        let error = SolverPipeline::from_names("tripples").unwrap_err();
+        assert!(error.to_string().ends_with("did you mean \"triples\"?"));
+        let error = SolverPipeline::from_names("guessing").unwrap_err();
+        assert_eq!(error.to_string(), "unknown technique \"guessing\"");
+    }
+
+    #[test]
+    fn cascades_keep_every_placement() {
+        let mut game = Game::new(gameboards::EASY);
+        let steps = game.solve_logical();
+        let grouped = Step::group_cascades(steps.clone());
+        assert!(grouped.len() < steps.len());
+        let placements = |steps: &[Step]| {
+            steps
+                .iter()
+                .flat_map(|step| step.placements.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(placements(&grouped), placements(&steps));
+        for step in &grouped {
+            match step.technique {
+                Technique::Cascade => {
+                    assert!(step.placements.len() > 1);
+                    assert!(step.eliminations.is_empty());
+                    for (i, &(y, x, _)) in step.placements.iter().enumerate().skip(1) {
+                        assert!(step.placements[..i]
+                            .iter()
+                            .any(|&(py, px, _)| Game::are_peers((py, px), (y, x))));
+                    }
+                }
+                _ => assert!(steps.contains(step)),
+            }
+        }
+        assert!(grouped
+            .iter()
+            .any(|step| step.technique == Technique::Cascade));
+        // A lone naked single isn't a cascade.
+        let single = steps
+            .iter()
+            .find(|step| step.technique == Technique::NakedSingle)
+            .unwrap()
+            .clone();
+        assert_eq!(Step::group_cascades(vec![single.clone()]), [single]);
+    }
+
+    // Every fourth position a logical solve passes through, for boards that need techniques from
+    // every tier
+    fn sampled_positions() -> Vec<Game> {
+        let boards = [
+            gameboards::MEDIUM,
+            gameboards::HARD,
+            gameboards::X_CHAIN,
+            gameboards::GOLDEN_NUGGET,
+        ];
+        let mut positions = Vec::new();
+        for &board in &boards {
+            let mut game = Game::new(board);
+            let mut taken = 0;
+            while let Some(step) = Technique::ALL.iter().find_map(|&t| game.find_step(t)) {
+                if taken % 4 == 0 {
+                    positions.push(game);
+                }
+                game.apply_step(&step);
+                taken += 1;
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn preview_then_apply_matches_applying_directly() {
+        let positions = sampled_positions();
+        assert!(positions.len() > 40);
+        let mut used = TechniqueSet::empty();
+        for game in positions {
+            for &technique in &Technique::ALL {
+                let preview = game.preview_technique(technique);
+                let mut direct = game;
+                let applied = direct.apply_technique(technique);
+                assert_eq!(preview, applied, "{:?} on\n{}", technique, game);
+                let mut previewed = game;
+                if let Some(step) = &preview {
+                    used.insert(technique);
+                    previewed.apply_step(step);
+                }
+                assert_eq!(previewed.dump_state(), direct.dump_state());
+            }
+        }
+        assert!(
+            used.iter().count() > 10,
+            "{:?}",
+            used.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn default_pipeline_solves_like_solve_logical() {
+        for &board in &[gameboards::HARD, gameboards::X_CHAIN] {
+            let mut by_pipeline = Game::new(board);
+            let mut logical = by_pipeline;
+            assert_eq!(
+                by_pipeline.solve_with_pipeline(&SolverPipeline::default()),
+                logical.solve_logical()
+            );
+            assert_eq!(by_pipeline.to_array(), logical.to_array());
+        }
+    }
+
+    #[test]
+    fn finned_x_wing() {
+        // Rows 1 and 5 make an X-wing on columns 2 and 7, but for the fin at R5C8. Either the
+        // X-wing holds or R5C8 is a 1, so the rest of column 7 in the fin's box can't be.
+        let game = ones_only_in(&[(0, &[1, 6]), (4, &[1, 6, 7])]);
+        assert!(game.find_fish(Technique::XWing, 2, false).is_none());
+        assert_eq!(fish_eliminations(&game, 2), [(3, 6), (5, 6)]);
+    }
+
+    #[test]
+    fn sashimi_x_wing() {
+        // As above, with R5C7 gone as well: the fin still covers for it.
+        let game = ones_only_in(&[(0, &[1, 6]), (4, &[1, 7])]);
+        assert_eq!(fish_eliminations(&game, 2), [(3, 6), (5, 6)]);
+    }
+
+    #[test]
+    fn logic_agrees_with_stored_solutions() {
+        for named in gameboards::with_solutions() {
+            let solution = match named.solution {
+                Some(solution) => solution,
+                None => continue,
+            };
+            let right = |(y, x, cv): (usize, usize, CellValue)| solution[y][x] == cv as u8;
+            let mut game = Game::new(named.board);
+            loop {
+                // The finned fish get checked wherever they turn up, not only when nothing
+                // cheaper does.
+                for &size in &[2, 3] {
+                    if let Some(step) = game.find_fish(Technique::FinnedXWing, size, true) {
+                        for &elim in &step.eliminations {
+                            assert!(!right(elim), "{}: {:?}", named.name, elim);
+                        }
+                    }
+                }
+                let step = match Technique::ALL.iter().find_map(|&t| game.find_step(t)) {
+                    Some(step) => step,
+                    None => break,
+                };
+                for &placement in &step.placements {
+                    assert!(right(placement), "{}: {:?}", named.name, step);
+                }
+                for &elim in &step.eliminations {
+                    assert!(!right(elim), "{}: {:?}", named.name, step);
+                }
+                game.apply_step(&step);
+            }
+        }
+    }
+
+    #[test]
+    fn x_chains_crack_their_puzzle() {
+        let without = Technique::ALL
+            .iter()
+            .copied()
+            .filter(|&t| {
+                t != Technique::XChain && t != Technique::ForcingChain && t != Technique::Nishio
+            })
+            .collect::<Vec<_>>();
+        let mut game = Game::new(gameboards::X_CHAIN);
+        game.solve_logical_with(&without, false);
+        assert!(!game.solved());
+
+        let mut with = without;
+        with.push(Technique::XChain);
+        let mut game = Game::new(gameboards::X_CHAIN);
+        let steps = game.solve_logical_with(&with, false);
+        assert!(game.solved());
+        assert_eq!(game.to_array(), gameboards::X_CHAIN_SOLUTION);
+        let x_chains = steps
+            .iter()
+            .filter(|step| step.technique == Technique::XChain)
+            .collect::<Vec<_>>();
+        assert!(!x_chains.is_empty());
+        for step in x_chains {
+            assert!(step.placements.is_empty());
+            for &(y, x, cv) in &step.eliminations {
+                assert_ne!(gameboards::X_CHAIN_SOLUTION[y][x], cv as u8, "{:?}", step);
+            }
+        }
+    }
+}
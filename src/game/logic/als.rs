@@ -0,0 +1,239 @@
+// Almost locked sets: `n` cells in one unit with `n + 1` candidates between them. Lose any one of
+// those values and the rest are locked into the cells, one each.
+
+use super::{combinations, mask_values, unit_cells, Step, Technique};
+use crate::game::{CellValue, Coord, Game};
+
+// The most cells an almost locked set can have. Bigger sets are rarely useful and the number of
+// them grows quickly.
+const MAX_ALS_CELLS: usize = 5;
+
+// Sets of cells are bitmasks with bit `9 * row + col` for each cell.
+type CellSet = u128;
+
+fn cell_bit((y, x): Coord) -> CellSet {
+    1 << Game::index(y, x)
+}
+
+fn set_cells(mut set: CellSet) -> impl Iterator<Item = Coord> {
+    std::iter::from_fn(move || {
+        let k = set.trailing_zeros() as usize;
+        set &= set.wrapping_sub(1);
+        (k < 81).then_some(Game::coords(k))
+    })
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Als {
+    cells: CellSet,
+    // The values the cells have between them
+    values: u16,
+    // For each value, the cells in the set that can take it
+    holders: [CellSet; 9],
+}
+
+impl Game {
+    // Every almost locked set of up to `MAX_ALS_CELLS` cells, each listed once even when its
+    // cells share both a 3x3 and a row or column.
+    fn almost_locked_sets(&self) -> Vec<Als> {
+        let mut sets = Vec::new();
+        for unit in 0..27 {
+            let empty = unit_cells(unit)
+                .filter(|&(y, x)| self.poss_mask(y, x) != 0)
+                .collect::<Vec<_>>();
+            for n in 1..=MAX_ALS_CELLS.min(empty.len()) {
+                for chosen in combinations(empty.len(), n) {
+                    let cells = mask_values(chosen).map(|j| empty[j]).collect::<Vec<_>>();
+                    // Cells of a 3x3 that all share a row or column were already found there.
+                    if unit >= 18
+                        && (cells.iter().all(|&(y, _)| y == cells[0].0)
+                            || cells.iter().all(|&(_, x)| x == cells[0].1))
+                    {
+                        continue;
+                    }
+                    let mut als = Als {
+                        cells: 0,
+                        values: 0,
+                        holders: [0; 9],
+                    };
+                    for &cell in &cells {
+                        let mask = self.poss_mask(cell.0, cell.1);
+                        als.cells |= cell_bit(cell);
+                        als.values |= mask;
+                        for i in mask_values(mask) {
+                            als.holders[i] |= cell_bit(cell);
+                        }
+                    }
+                    if als.values.count_ones() as usize == n + 1 {
+                        sets.push(als);
+                    }
+                }
+            }
+        }
+        sets
+    }
+
+    // Two almost locked sets `a` and `b` that don't overlap, where every place for some value `x`
+    // in `a` sees every place for it in `b`. At most one of the sets can hold `x`, so the other
+    // loses it and locks its remaining values. Then for any other value `z` the sets share, one of
+    // them has to hold `z`, so no cell that sees every place for `z` in both sets can.
+    pub(super) fn find_als_xz(&self) -> Option<Step> {
+        let mut peers = [0 as CellSet; 81];
+        for (k, set) in peers.iter_mut().enumerate() {
+            *set = (0..81)
+                .map(Game::coords)
+                .filter(|&other| Game::are_peers(Game::coords(k), other))
+                .fold(0, |set, other| set | cell_bit(other));
+        }
+        let mut holders = [0 as CellSet; 9];
+        for (y, x) in (0..81).map(Game::coords) {
+            for i in mask_values(self.poss_mask(y, x)) {
+                holders[i] |= cell_bit((y, x));
+            }
+        }
+        // The cells that see every cell in a set
+        let seen_by_all = |set: CellSet| {
+            set_cells(set).fold(!0 as CellSet, |seen, (y, x)| {
+                seen & peers[Game::index(y, x)]
+            })
+        };
+        let sets = self.almost_locked_sets();
+        for (j, a) in sets.iter().enumerate() {
+            for b in &sets[j + 1..] {
+                let common = a.values & b.values;
+                if a.cells & b.cells != 0 || common.count_ones() < 2 {
+                    continue;
+                }
+                for x in mask_values(common) {
+                    let restricted = set_cells(a.holders[x])
+                        .all(|(y, col)| b.holders[x] & !peers[Game::index(y, col)] == 0);
+                    if !restricted {
+                        continue;
+                    }
+                    let eliminations = mask_values(common & !(1 << x))
+                        .flat_map(|z| {
+                            let targets = seen_by_all(a.holders[z] | b.holders[z])
+                                & holders[z]
+                                & !(a.cells | b.cells);
+                            set_cells(targets)
+                                .map(move |(y, col)| (y, col, CellValue::from_index(z)))
+                        })
+                        .collect::<Vec<_>>();
+                    if !eliminations.is_empty() {
+                        return Some(Step::eliminations(Technique::AlsXz, eliminations));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Makes one elimination with ALS-XZ, using almost locked sets of up to five cells. Returns
+    /// whether anything was eliminated.
+    pub fn apply_als_xz(&mut self) -> bool {
+        self.apply_technique(Technique::AlsXz).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{CellValue, Game, Technique};
+    use crate::gameboards;
+    use std::time::{Duration, Instant};
+
+    // Puzzles from the rating fixtures that need ALS-XZ, each with the elimination it makes first
+    const POSITIONS: [(&str, (usize, usize, CellValue)); 4] = [
+        (
+            ".2.7.6..9.6..5..4.......3.....3......1....6.72.5.67..1.....572.7....3...459......",
+            (5, 6, CellValue::Eight),
+        ),
+        (
+            ".1..6...4..5...1..4....3.......1..2..38...7......47.8...1.52....6...48....98...56",
+            (0, 2, CellValue::Two),
+        ),
+        (
+            "....3..2.8...4......47.93...5...7......6..9..37..1..5.1......69248.....1....7...2",
+            (3, 4, CellValue::Two),
+        ),
+        (
+            "...94...........8......73.58...1..6...3.....174....8..6..5.3.97...........84...1.",
+            (3, 6, CellValue::Nine),
+        ),
+    ];
+
+    // Solves with every technique cheaper than ALS-XZ until they get stuck.
+    fn stuck(game: &mut Game) {
+        let cheaper = Technique::ALL
+            .iter()
+            .copied()
+            .take_while(|&t| t != Technique::AlsXz)
+            .collect::<Vec<_>>();
+        game.solve_logical_with(&cheaper, false);
+    }
+
+    #[test]
+    fn finds_the_first_elimination() {
+        for &(puzzle, elimination) in &POSITIONS {
+            let mut game = puzzle.parse::<Game>().unwrap();
+            stuck(&mut game);
+            let step = game.find_als_xz().unwrap();
+            assert_eq!(step.technique, Technique::AlsXz);
+            assert!(step.placements.is_empty());
+            assert_eq!(step.eliminations, [elimination], "{}", puzzle);
+        }
+    }
+
+    #[test]
+    fn never_eliminates_the_solution() {
+        for &(puzzle, _) in &POSITIONS {
+            let mut game = puzzle.parse::<Game>().unwrap();
+            let mut solution = game;
+            solution.solve().unwrap();
+            loop {
+                stuck(&mut game);
+                let step = match game.find_als_xz() {
+                    Some(step) => step,
+                    None => break,
+                };
+                for &(y, x, cv) in &step.eliminations {
+                    assert_ne!(solution.board[y][x], Some(cv), "{}", puzzle);
+                }
+                game.apply_step(&step);
+            }
+        }
+    }
+
+    // Only the hardest boards get far enough to need ALS-XZ, and there are a lot of almost locked
+    // sets in what's left of them. A search there takes about a millisecond with optimizations.
+    #[test]
+    fn runs_in_a_few_milliseconds() {
+        let boards = [
+            gameboards::AI_ESCARGOT,
+            gameboards::EASTER_MONSTER,
+            gameboards::GOLDEN_NUGGET,
+            gameboards::PLATINUM_BLONDE,
+        ];
+        let games = boards
+            .iter()
+            .map(|&board| Game::new(board))
+            .chain(POSITIONS.iter().map(|(puzzle, _)| puzzle.parse().unwrap()));
+        for mut game in games {
+            stuck(&mut game);
+            // The best of a few runs, so a busy machine doesn't fail the test
+            let fastest = (0..3)
+                .map(|_| {
+                    let start = Instant::now();
+                    game.find_als_xz();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+            assert!(
+                fastest < Duration::from_millis(10),
+                "ALS-XZ took {:?} on\n{}",
+                fastest,
+                game
+            );
+        }
+    }
+}
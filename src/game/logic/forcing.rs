@@ -0,0 +1,237 @@
+// Forcing chains and Nishio, the last resort before guessing: assume a candidate, follow the cheap
+// techniques from there, and if that runs into a contradiction, the candidate was wrong.
+
+use super::unit_cells;
+use super::{Step, Technique};
+use crate::game::{CellValue, Coord, Game};
+
+// The techniques followed after an assumption. Only singles, so that each probe stays cheap and the
+// chain reads as a simple sequence of forced placements.
+const PROBE_TECHNIQUES: [Technique; 3] = [
+    Technique::FullHouse,
+    Technique::NakedSingle,
+    Technique::HiddenSingle,
+];
+
+/// How far forcing chains are allowed to look.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ChainLimits {
+    /// Only make assumptions in cells with at most this many candidates
+    pub max_candidates: usize,
+    /// Give up on an assumption after this many steps without a contradiction
+    pub max_steps: usize,
+}
+
+impl Default for ChainLimits {
+    fn default() -> Self {
+        ChainLimits {
+            max_candidates: 2,
+            max_steps: 40,
+        }
+    }
+}
+
+/// Ways the board can break after an assumption.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Contradiction {
+    /// An empty cell was left without any candidates
+    NoCandidates(Coord),
+    /// A unit was left with nowhere to put a value. Units are numbered with rows as 0-8, columns
+    /// as 9-17, and 3x3s as 18-26.
+    NoPlace { unit: usize, value: CellValue },
+}
+
+/// Why a forcing chain step crossed off its candidate: the assumption it made, the steps that
+/// followed from it, and the contradiction they led to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ForcingChain {
+    pub assumption: (usize, usize, CellValue),
+    pub steps: Vec<Step>,
+    pub contradiction: Contradiction,
+}
+
+impl Game {
+    fn contradiction(&self) -> Option<Contradiction> {
+        if let Some((y, x, _, _)) = self
+            .iter()
+            .find(|&(_, _, cell, poss)| cell.is_none() && !poss.contains(&true))
+        {
+            return Some(Contradiction::NoCandidates((y, x)));
+        }
+        for unit in 0..27 {
+            for i in 0..9 {
+                let value = CellValue::from_index(i);
+                let placed = unit_cells(unit).any(|(y, x)| self.board[y][x] == Some(value));
+                if !placed && self.unit_positions(unit, i).is_empty() {
+                    return Some(Contradiction::NoPlace { unit, value });
+                }
+            }
+        }
+        None
+    }
+
+    // Assumes a value in a cell and follows singles from there, returning the chain if it ends in
+    // a contradiction within the step limit.
+    fn probe(&self, y: usize, x: usize, cv: CellValue, max_steps: usize) -> Option<ForcingChain> {
+        let mut game = self.what_if();
+        game.set_cell(y, x, cv);
+        let mut steps = Vec::new();
+        loop {
+            if let Some(contradiction) = game.contradiction() {
+                return Some(ForcingChain {
+                    assumption: (y, x, cv),
+                    steps,
+                    contradiction,
+                });
+            }
+            if steps.len() >= max_steps || game.solved() {
+                return None;
+            }
+            let step = PROBE_TECHNIQUES.iter().find_map(|&t| game.find_step(t))?;
+            game.apply_step(&step);
+            steps.push(step);
+        }
+    }
+
+    pub(super) fn find_forcing_chain(&self, limits: ChainLimits) -> Option<Step> {
+        self.find_chain(Technique::ForcingChain, limits)
+    }
+
+    // Nishio is a forcing chain from any candidate at all, followed for as long as the singles
+    // last. Singles can't fill more than the board, so that's the step limit.
+    pub(super) fn find_nishio(&self) -> Option<Step> {
+        let limits = ChainLimits {
+            max_candidates: 9,
+            max_steps: 81,
+        };
+        self.find_chain(Technique::Nishio, limits)
+    }
+
+    fn find_chain(&self, technique: Technique, limits: ChainLimits) -> Option<Step> {
+        // Cells with fewer candidates first, since those are the assumptions most likely to be
+        // wrong.
+        for n in 2..=limits.max_candidates.min(9) {
+            for (y, x, _, _) in self.iter().filter(|&(_, _, cell, poss)| {
+                cell.is_none() && poss.iter().filter(|&&p| p).count() == n
+            }) {
+                for cv in self.candidates((y, x)).iter() {
+                    if let Some(chain) = self.probe(y, x, cv, limits.max_steps) {
+                        return Some(Step {
+                            technique,
+                            placements: Vec::new(),
+                            eliminations: vec![(y, x, cv)],
+                            chain: Some(Box::new(chain)),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Crosses off one candidate by assuming it and finding that it leads to a contradiction,
+    /// within the given limits. Returns whether anything was crossed off. The logical solver uses
+    /// this with the default limits, and only Nishio after it; the search in `solve` never does.
+    pub fn apply_forcing_chains(&mut self, limits: ChainLimits) -> bool {
+        match self.find_forcing_chain(limits) {
+            Some(step) => {
+                self.apply_step(&step);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A rating fixture that needs forcing chains but not Nishio
+    const FORCING_CHAIN: &str =
+        "....3..2.8...4......47.93...5...7......6..9..37..1..5.1......69248.....1....7...2";
+
+    // The rating fixture that needs Nishio
+    const NISHIO: &str =
+        "...94...........8......73.58...1..6...3.....174....8..6..5.3.97...........84...1.";
+
+    // Solves with every technique cheaper than `technique` until they get stuck.
+    fn stuck(puzzle: &str, technique: Technique) -> Game {
+        let mut game = puzzle.parse::<Game>().unwrap();
+        let cheaper = Technique::ALL
+            .iter()
+            .copied()
+            .take_while(|&t| t != technique)
+            .collect::<Vec<_>>();
+        game.solve_logical_with(&cheaper, false);
+        game
+    }
+
+    fn solution(puzzle: &str) -> Game {
+        let mut solution = puzzle.parse::<Game>().unwrap();
+        solution.solve().unwrap();
+        solution
+    }
+
+    #[test]
+    fn forcing_chains_finish_what_the_rest_cant() {
+        let mut game = stuck(FORCING_CHAIN, Technique::ForcingChain);
+        assert!(!game.solved());
+        let with_chains = Technique::ALL
+            .iter()
+            .copied()
+            .take_while(|&t| t != Technique::Nishio)
+            .collect::<Vec<_>>();
+        let steps = game.solve_logical_with(&with_chains, false);
+        assert!(game.solved());
+        assert_eq!(game.to_array(), solution(FORCING_CHAIN).to_array());
+        assert!(steps
+            .iter()
+            .any(|step| step.technique == Technique::ForcingChain));
+    }
+
+    #[test]
+    fn forcing_chains_explain_themselves() {
+        let game = stuck(FORCING_CHAIN, Technique::ForcingChain);
+        let step = game.find_forcing_chain(ChainLimits::default()).unwrap();
+        assert_eq!(step.eliminations, [(0, 2, CellValue::Nine)]);
+        assert_ne!(solution(FORCING_CHAIN).board[0][2], Some(CellValue::Nine));
+        let chain = step.chain.unwrap();
+        assert_eq!(chain.assumption, (0, 2, CellValue::Nine));
+        assert_eq!(
+            chain.contradiction,
+            Contradiction::NoPlace {
+                unit: 5,
+                value: CellValue::Eight
+            }
+        );
+        // Following the steps from the assumption ends in the contradiction, and not before.
+        let mut after = game;
+        after.set_cell(0, 2, CellValue::Nine);
+        for chain_step in &chain.steps {
+            assert_eq!(after.contradiction(), None);
+            assert!(PROBE_TECHNIQUES.contains(&chain_step.technique));
+            assert_eq!(
+                after.find_step(chain_step.technique).as_ref(),
+                Some(chain_step)
+            );
+            after.apply_step(chain_step);
+        }
+        assert_eq!(after.contradiction(), Some(chain.contradiction));
+        assert!(!chain.steps.is_empty() && chain.steps.len() <= ChainLimits::default().max_steps);
+    }
+
+    #[test]
+    fn nishio_crosses_off_a_wrong_candidate() {
+        let game = stuck(NISHIO, Technique::Nishio);
+        assert!(!game.solved());
+        assert!(game.find_forcing_chain(ChainLimits::default()).is_none());
+        let step = game.find_nishio().unwrap();
+        assert_eq!(step.eliminations, [(0, 8, CellValue::Two)]);
+        assert_eq!(solution(NISHIO).board[0][8], Some(CellValue::Six));
+        // Longer than a forcing chain is allowed to follow
+        let chain = step.chain.unwrap();
+        assert_eq!(chain.assumption, (0, 8, CellValue::Two));
+        assert!(chain.steps.len() > ChainLimits::default().max_steps);
+    }
+}
@@ -0,0 +1,360 @@
+// Techniques that follow links between the places a value can go. A strong link is a unit with
+// only two places left for a value, so one of the two has to hold it. A weak link is two cells that
+// see each other, so at most one of them can hold it.
+
+use super::{mask_values, Step, Technique};
+use crate::game::{CellValue, Coord, Game};
+use std::collections::VecDeque;
+
+// A unit with exactly two places left for some value
+#[derive(Copy, Clone, Debug)]
+pub(super) struct StrongLink {
+    pub(super) unit: usize,
+    pub(super) ends: (Coord, Coord),
+}
+
+// Which of the turbot fish a chain is, going by where its two strong links are and what links the
+// inner ends `b` and `c`.
+fn turbot_kind(first: usize, second: usize, b: Coord, c: Coord) -> Technique {
+    // 0 for rows, 1 for columns, and 2 for 3x3s
+    let (first, second) = (first / 9, second / 9);
+    let linked_across = match first {
+        0 => b.1 == c.1,
+        _ => b.0 == c.0,
+    };
+    if first == second && first < 2 && linked_across {
+        Technique::Skyscraper
+    } else if first + second == 1 && b.0 / 3 == c.0 / 3 && b.1 / 3 == c.1 / 3 {
+        Technique::TwoStringKite
+    } else {
+        Technique::TurbotFish
+    }
+}
+
+impl Game {
+    // Every strong link for the value at index `i`.
+    pub(super) fn strong_links_for(&self, i: usize) -> Vec<StrongLink> {
+        (0..27)
+            .filter_map(|unit| match self.unit_positions(unit, i)[..] {
+                [a, b] => Some(StrongLink { unit, ends: (a, b) }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every pair of cells that are the only two places left for a value in some row, column, or
+    /// 3x3, so that one or the other has to hold it. These are the strong links that chaining
+    /// techniques follow. A pair that's the only two places in more than one unit is only listed
+    /// once.
+    pub fn strong_links(&self, value: CellValue) -> Vec<(Coord, Coord)> {
+        let mut links = Vec::new();
+        for link in self.strong_links_for(usize::from(value)) {
+            if !links.contains(&link.ends) {
+                links.push(link.ends);
+            }
+        }
+        links
+    }
+
+    // Turbot fish are chains of a strong link, a weak link, and another strong link, `a`-`b`,
+    // `b`-`c`, `c`-`d`. If `a` doesn't hold the value then `b` does, so `c` doesn't, so `d` does.
+    // Either way one of the ends holds it, so no cell that sees both ends can.
+    pub(super) fn find_turbot_fish(&self, technique: Technique) -> Option<Step> {
+        for i in 0..9 {
+            let links = self.strong_links_for(i);
+            for (j, first) in links.iter().enumerate() {
+                for second in links.iter().skip(j + 1) {
+                    let (p, q) = first.ends;
+                    let (r, s) = second.ends;
+                    for &(a, b) in &[(p, q), (q, p)] {
+                        for &(c, d) in &[(r, s), (s, r)] {
+                            if [a, b].iter().any(|cell| *cell == c || *cell == d)
+                                || !Game::are_peers(b, c)
+                                || turbot_kind(first.unit, second.unit, b, c) != technique
+                            {
+                                continue;
+                            }
+                            let eliminations = (0..81)
+                                .map(Game::coords)
+                                .filter(|&cell| {
+                                    Game::are_peers(a, cell)
+                                        && Game::are_peers(d, cell)
+                                        && self.poss_mask(cell.0, cell.1) & 1 << i != 0
+                                })
+                                .map(|(y, x)| (y, x, CellValue::from_index(i)))
+                                .collect::<Vec<_>>();
+                            if !eliminations.is_empty() {
+                                return Some(Step::eliminations(technique, eliminations));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Cells with the same two candidates that see each other can't hold the same value, so along
+    // any chain of them the two values alternate. Coloring each connected group of such cells in
+    // two colors, one color holds one value and the other color holds the other, so a cell that
+    // sees cells of both colors can hold neither. This colors whole groups, branches and cycles
+    // included, rather than following simple paths, and leaves alone any group that can't be
+    // colored consistently since that means the board has a contradiction. When the two colored
+    // cells see each other they're just a naked pair, so those are left to that technique.
+    pub(super) fn find_remote_pairs(&self) -> Option<Step> {
+        let cells = (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| self.poss_mask(y, x).count_ones() == 2)
+            .collect::<Vec<_>>();
+        let mut colors = vec![None; cells.len()];
+        for start in 0..cells.len() {
+            if colors[start].is_some() {
+                continue;
+            }
+            let pair = self.poss_mask(cells[start].0, cells[start].1);
+            colors[start] = Some(false);
+            let mut group = vec![start];
+            let mut consistent = true;
+            let mut next = 0;
+            while let Some(&j) = group.get(next) {
+                next += 1;
+                let color = !colors[j].unwrap();
+                for (k, &cell) in cells.iter().enumerate() {
+                    if self.poss_mask(cell.0, cell.1) != pair || !Game::are_peers(cells[j], cell) {
+                        continue;
+                    }
+                    match colors[k] {
+                        None => {
+                            colors[k] = Some(color);
+                            group.push(k);
+                        }
+                        Some(other) if other != color => consistent = false,
+                        Some(_) => {}
+                    }
+                }
+            }
+            // Two cells of different colors that don't see each other are at least three links
+            // apart, which takes at least four cells.
+            if !consistent || group.len() < 4 {
+                continue;
+            }
+            let (even, odd): (Vec<_>, Vec<_>) = group
+                .iter()
+                .map(|&j| (cells[j], colors[j].unwrap()))
+                .partition(|&(_, color)| color);
+            let eliminations = (0..81)
+                .map(Game::coords)
+                .filter(|&target| {
+                    !group.iter().any(|&j| cells[j] == target)
+                        && self.poss_mask(target.0, target.1) & pair != 0
+                        && even.iter().any(|&(e, _)| {
+                            Game::are_peers(target, e)
+                                && odd.iter().any(|&(o, _)| {
+                                    Game::are_peers(target, o) && !Game::are_peers(e, o)
+                                })
+                        })
+                })
+                .flat_map(|(y, x)| {
+                    mask_values(self.poss_mask(y, x) & pair)
+                        .map(move |i| (y, x, CellValue::from_index(i)))
+                })
+                .collect::<Vec<_>>();
+            if !eliminations.is_empty() {
+                return Some(Step::eliminations(Technique::RemotePairs, eliminations));
+            }
+        }
+        None
+    }
+
+    // An X-chain is a chain of places for one value where the links alternate strong, weak,
+    // strong, and so on, ending with a strong link. If the first place doesn't hold the value then
+    // the second does, so the third doesn't, and so on down the chain to the last place, which
+    // does. So one of the two ends holds it, and no cell that sees both can. This searches out
+    // from each place breadth first, so shorter chains are found first, and the shortest ones are
+    // the simpler techniques that come before it: a single strong link is locked candidates, and
+    // three links are a turbot fish. Every step of the reasoning holds even if the chain comes
+    // back through a place it's already been, so a walk is as good as a path here.
+    pub(super) fn find_x_chain(&self) -> Option<Step> {
+        for i in 0..9 {
+            let links = self.strong_links_for(i);
+            let places = (0..81)
+                .map(Game::coords)
+                .filter(|&(y, x)| self.poss_mask(y, x) & 1 << i != 0)
+                .collect::<Vec<_>>();
+            let index = |cell: Coord| places.iter().position(|&p| p == cell).unwrap();
+            for (start, &a) in places.iter().enumerate() {
+                // Whether each place has been reached with the next link strong, or weak
+                let mut seen = vec![[false; 2]; places.len()];
+                seen[start][0] = true;
+                let mut queue = VecDeque::from(vec![(start, 0)]);
+                while let Some((at, parity)) = queue.pop_front() {
+                    let z = places[at];
+                    let next = if parity == 0 {
+                        links
+                            .iter()
+                            .filter_map(|link| match link.ends {
+                                (p, q) if p == z => Some(q),
+                                (p, q) if q == z => Some(p),
+                                _ => None,
+                            })
+                            .map(index)
+                            .collect::<Vec<_>>()
+                    } else {
+                        // Coming back to the start would mean it holds the value, which is for
+                        // other techniques to find.
+                        if z != a {
+                            let eliminations = places
+                                .iter()
+                                .filter(|&&p| Game::are_peers(a, p) && Game::are_peers(z, p))
+                                .map(|&(y, x)| (y, x, CellValue::from_index(i)))
+                                .collect::<Vec<_>>();
+                            if !eliminations.is_empty() {
+                                return Some(Step::eliminations(Technique::XChain, eliminations));
+                            }
+                        }
+                        (0..places.len())
+                            .filter(|&p| Game::are_peers(z, places[p]))
+                            .collect()
+                    };
+                    for p in next {
+                        if !seen[p][1 - parity] {
+                            seen[p][1 - parity] = true;
+                            queue.push_back((p, 1 - parity));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Makes one elimination with remote pairs. Returns whether anything was eliminated.
+    pub fn apply_remote_pairs(&mut self) -> bool {
+        self.apply_technique(Technique::RemotePairs).is_some()
+    }
+
+    /// Makes one elimination with a skyscraper, two-string kite, or other turbot fish, trying them
+    /// in that order. Returns whether anything was eliminated.
+    pub fn apply_turbot_fish(&mut self) -> bool {
+        let techniques = [
+            Technique::Skyscraper,
+            Technique::TwoStringKite,
+            Technique::TurbotFish,
+        ];
+        techniques
+            .iter()
+            .any(|&t| self.apply_technique(t).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::logic::unit_cells;
+    use crate::gameboards::ZEROS;
+
+    // An empty board with 1 left as a candidate only in the given cells of the given rows,
+    // columns, and 3x3s, so the 1s make just the strong links being tested.
+    fn ones_only_at(units: &[(usize, &[Coord])]) -> Game {
+        let mut game = Game::new(ZEROS);
+        for &(unit, keep) in units {
+            for cell in unit_cells(unit).filter(|cell| !keep.contains(cell)) {
+                game.eliminate_candidate(cell, CellValue::One).unwrap();
+            }
+        }
+        game
+    }
+
+    // An empty board with the given cells cut down to 1 and 2.
+    fn pairs_at(cells: &[Coord]) -> Game {
+        let mut game = Game::new(ZEROS);
+        for &cell in cells {
+            for &cv in &CellValue::ALL[2..] {
+                game.eliminate_candidate(cell, cv).unwrap();
+            }
+        }
+        game
+    }
+
+    // Both values crossed off each of the cells
+    fn both_off(cells: &[Coord]) -> Vec<(usize, usize, CellValue)> {
+        cells
+            .iter()
+            .flat_map(|&(y, x)| vec![(y, x, CellValue::One), (y, x, CellValue::Two)])
+            .collect()
+    }
+
+    fn eliminations(game: &Game, technique: Technique) -> Vec<(usize, usize, CellValue)> {
+        let step = game.find_turbot_fish(technique).unwrap();
+        assert_eq!(step.technique, technique);
+        assert!(step.placements.is_empty());
+        step.eliminations
+    }
+
+    #[test]
+    fn skyscraper() {
+        // Rows 1 and 5 share column 2, so one of R1C6 and R5C4 is a 1.
+        let game = ones_only_at(&[(0, &[(0, 1), (0, 5)]), (4, &[(4, 1), (4, 3)])]);
+        let one = CellValue::One;
+        assert_eq!(
+            eliminations(&game, Technique::Skyscraper),
+            [(1, 3, one), (2, 3, one), (3, 5, one), (5, 5, one)]
+        );
+        assert!(game.find_turbot_fish(Technique::TwoStringKite).is_none());
+        assert!(game.find_turbot_fish(Technique::TurbotFish).is_none());
+    }
+
+    #[test]
+    fn two_string_kite() {
+        // Row 1 and column 3 meet in the top left 3x3, so one of R1C7 and R7C3 is a 1.
+        let game = ones_only_at(&[(0, &[(0, 1), (0, 6)]), (11, &[(2, 2), (6, 2)])]);
+        assert_eq!(
+            eliminations(&game, Technique::TwoStringKite),
+            [(6, 6, CellValue::One)]
+        );
+        assert!(game.find_turbot_fish(Technique::Skyscraper).is_none());
+        assert!(game.find_turbot_fish(Technique::TurbotFish).is_none());
+    }
+
+    #[test]
+    fn turbot_fish() {
+        // Row 1 and the middle right 3x3 are linked through column 8, so one of R1C2 and R6C9 is
+        // a 1.
+        let game = ones_only_at(&[(0, &[(0, 1), (0, 7)]), (23, &[(3, 7), (5, 8)])]);
+        assert_eq!(
+            eliminations(&game, Technique::TurbotFish),
+            [(5, 1, CellValue::One)]
+        );
+        assert!(game.find_turbot_fish(Technique::Skyscraper).is_none());
+        assert!(game.find_turbot_fish(Technique::TwoStringKite).is_none());
+    }
+
+    #[test]
+    fn remote_pairs() {
+        // R1C1, R1C5, R5C5, R5C9 in a chain, so R1C1 and R5C9 hold different values.
+        let game = pairs_at(&[(0, 0), (0, 4), (4, 4), (4, 8)]);
+        let step = game.find_remote_pairs().unwrap();
+        assert_eq!(step.technique, Technique::RemotePairs);
+        assert_eq!(step.eliminations, both_off(&[(0, 8), (4, 0)]));
+        // Three cells aren't enough for the ends not to see each other.
+        assert!(pairs_at(&[(0, 0), (0, 4), (4, 4)])
+            .find_remote_pairs()
+            .is_none());
+    }
+
+    #[test]
+    fn remote_pairs_color_whole_groups() {
+        // R1C5 branches to R1C1, R2C4, and R5C5, which goes on to R5C9. R5C9 is the other color
+        // from the ends of both other branches, and both branches count in the same step.
+        let game = pairs_at(&[(0, 4), (0, 0), (1, 3), (4, 4), (4, 8)]);
+        let step = game.find_remote_pairs().unwrap();
+        assert_eq!(
+            step.eliminations,
+            both_off(&[(0, 8), (1, 8), (4, 0), (4, 3)])
+        );
+        // A cycle of five can't be colored, which only happens on a board with a contradiction,
+        // so it's left alone.
+        let game = pairs_at(&[(0, 0), (0, 4), (4, 4), (4, 1), (1, 1)]);
+        assert!(game.find_remote_pairs().is_none());
+    }
+}
@@ -0,0 +1,72 @@
+// Checking the logical solver against a known solution. A technique that fills in the wrong value
+// or crosses off the right one usually isn't noticed until much later, when the game turns out to
+// be unsolvable, so this catches it at the step that did it.
+
+use super::Step;
+use crate::game::Game;
+
+impl Game {
+    /// Arms the oracle: from now on, every step the logical techniques find is checked against
+    /// `solution`, and any step that fills in a value the solution doesn't have or crosses off one
+    /// it does panics with the step. Only the logical techniques are checked, not moves made by the
+    /// player or the backtracking search, so the game should agree with the solution when armed.
+    ///
+    /// Panics if `solution` isn't completely filled in.
+    pub fn set_oracle(&mut self, solution: Game) {
+        assert!(
+            solution.solved(),
+            "the oracle has to be a complete solution"
+        );
+        self.oracle = Some(solution.to_array());
+    }
+
+    pub(super) fn check_oracle(&self, step: &Step) {
+        let solution = match &self.oracle {
+            Some(solution) => solution,
+            None => return,
+        };
+        for &(y, x, cv) in &step.placements {
+            if solution[y][x] != cv as u8 {
+                panic!(
+                    "{} filled in {} at {}, but the solution has {}: {:?}",
+                    step.technique,
+                    cv as u8,
+                    Game::cell_name(y, x),
+                    solution[y][x],
+                    step
+                );
+            }
+        }
+        for &(y, x, cv) in &step.eliminations {
+            if solution[y][x] == cv as u8 {
+                panic!(
+                    "{} crossed off {} at {}, which is the solution: {:?}",
+                    step.technique,
+                    cv as u8,
+                    Game::cell_name(y, x),
+                    step
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameboards::{AI_ESCARGOT, AI_ESCARGOT_SOLUTION};
+    use crate::Game;
+
+    #[test]
+    fn forcing_chains_dont_trip_the_oracle() {
+        // The forcing chains and Nishio try wrong values on copies of the game, and what follows
+        // from them mustn't be checked against the solution.
+        let mut game = Game::new(AI_ESCARGOT);
+        game.set_oracle(Game::new(AI_ESCARGOT_SOLUTION));
+        game.solve_logical();
+        for (y, row) in game.to_array().iter().enumerate() {
+            for (x, &n) in row.iter().enumerate() {
+                assert!(n == 0 || n == AI_ESCARGOT_SOLUTION[y][x]);
+            }
+        }
+    }
+}
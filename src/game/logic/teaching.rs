@@ -0,0 +1,97 @@
+// Choosing hints for someone learning: of all the cells that could be filled in right now, the
+// one that leaves the rest of the puzzle closest to the difficulty they're practising, rather than
+// whichever single the solver happens to find first.
+
+use super::{unit_cells, Difficulty, Rating, Technique};
+use crate::game::{CellValue, Coord, Game};
+
+/// A cell that can be filled in right now with one of the singles techniques.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Hint {
+    pub row: usize,
+    pub col: usize,
+    pub value: CellValue,
+    /// The cheapest technique that finds the placement
+    pub technique: Technique,
+}
+
+/// What the rest of a puzzle looks like after a placement, from `Game::relative_difficulty_after`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HintOutcome {
+    /// The rating of the puzzle with the placement made
+    pub rating: Rating,
+    /// How many empty cells a single would fill in straight after the placement
+    pub forced: usize,
+}
+
+impl Game {
+    /// Every placement the singles techniques can make from the current state, one per cell, in
+    /// board order. A cell that's both a naked and a hidden single is listed under whichever is
+    /// cheaper. Empty when the next step has to be an elimination.
+    pub fn available_hints(&self) -> Vec<Hint> {
+        let mut found: [[Option<Hint>; 9]; 9] = [[None; 9]; 9];
+        let mut offer = |row: usize, col: usize, i: usize, technique: Technique| {
+            let cell = &mut found[row][col];
+            if cell.map_or(true, |hint| hint.technique > technique) {
+                *cell = Some(Hint {
+                    row,
+                    col,
+                    value: CellValue::from_index(i),
+                    technique,
+                });
+            }
+        };
+        for unit in 0..27 {
+            let mut empty = unit_cells(unit).filter(|&(y, x)| self.board[y][x].is_none());
+            if let (Some((y, x)), None) = (empty.next(), empty.next()) {
+                let mask = self.poss_mask(y, x);
+                if mask.count_ones() == 1 {
+                    offer(y, x, mask.trailing_zeros() as usize, Technique::FullHouse);
+                }
+            }
+            for i in 0..9 {
+                if let [(y, x)] = self.unit_positions(unit, i)[..] {
+                    offer(y, x, i, Technique::HiddenSingle);
+                }
+            }
+        }
+        for (y, x, _, _) in self.iter() {
+            let mask = self.poss_mask(y, x);
+            if mask.count_ones() == 1 {
+                offer(y, x, mask.trailing_zeros() as usize, Technique::NakedSingle);
+            }
+        }
+        found.iter().flatten().flatten().copied().collect()
+    }
+
+    /// Makes a placement on a copy of the game and rates what's left, along with how many cells
+    /// it leaves open to a single. Returns `None` if the cell is filled or can't take the value.
+    pub fn relative_difficulty_after(&self, coord: Coord, value: CellValue) -> Option<HintOutcome> {
+        let (row, col) = coord;
+        if row >= 9 || col >= 9 || self.poss_mask(row, col) & 1 << usize::from(value) == 0 {
+            return None;
+        }
+        let mut after = self.what_if();
+        after.set_cell(row, col, value);
+        Some(HintOutcome {
+            rating: after.rate(),
+            forced: after.available_hints().len(),
+        })
+    }
+
+    /// Picks the hint that keeps the rest of the puzzle closest to `target`. Among hints that do
+    /// equally well, the one leaving the fewest cells open to a single goes first, so a move that
+    /// keeps the solver looking is preferred over one that turns the rest into a run of singles.
+    /// Returns `None` if no single is available.
+    pub fn best_teaching_hint(&self, target: Difficulty) -> Option<Hint> {
+        self.available_hints()
+            .into_iter()
+            .filter_map(|hint| {
+                let outcome = self.relative_difficulty_after((hint.row, hint.col), hint.value)?;
+                let distance = (outcome.rating.difficulty as isize - target as isize).abs();
+                Some(((distance, outcome.forced), hint))
+            })
+            .min_by_key(|&(key, _)| key)
+            .map(|(_, hint)| hint)
+    }
+}
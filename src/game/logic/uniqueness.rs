@@ -0,0 +1,121 @@
+// Techniques that assume the game has exactly one solution, and rule out anything that would leave
+// it with more than one.
+
+use super::{mask_values, unit_cells, Step, Technique};
+use crate::game::Game;
+
+impl Game {
+    // A bivalue universal grave is a state where every empty cell has two candidates and every
+    // candidate appears exactly twice in each unit it's in. Such a state always has either no
+    // solutions or at least two, since the two places for each value can be swapped everywhere at
+    // once. With a single cell holding a third candidate, that cell must take whichever of its
+    // values would otherwise appear three times in its units, or the rest would be a grave.
+    pub(super) fn find_bug_plus_one(&self) -> Option<Step> {
+        // Tally the candidate counts first, since almost every board fails here.
+        let mut extra = None;
+        for (y, x) in (0..81).map(Game::coords) {
+            match self.poss_mask(y, x).count_ones() {
+                0 | 2 => {}
+                3 if extra.is_none() => extra = Some((y, x)),
+                _ => return None,
+            }
+        }
+        let (y, x) = extra?;
+        let units = [y, 9 + x, 18 + 3 * (y / 3) + x / 3];
+        let count = |unit: usize, i: usize| {
+            unit_cells(unit)
+                .filter(|&(y, x)| self.poss_mask(y, x) & 1 << i != 0)
+                .count()
+        };
+        let i = mask_values(self.poss_mask(y, x)).find(|&i| count(units[0], i) == 3)?;
+        for unit in 0..27 {
+            for j in 0..9 {
+                let expected = if j == i && units.contains(&unit) {
+                    3
+                } else {
+                    2
+                };
+                let n = count(unit, j);
+                if n != 0 && n != expected {
+                    return None;
+                }
+            }
+        }
+        Some(Step::placement(Technique::BugPlusOne, y, x, i))
+    }
+
+    /// Fills in a cell with BUG+1, which only holds if the game has exactly one solution. Calling
+    /// this takes that on trust; the logical solver itself only uses BUG+1 with
+    /// `SolverPipeline::assume_unique` set. Returns whether a cell was filled in.
+    pub fn apply_bug_plus_one(&mut self) -> bool {
+        self.apply_technique(Technique::BugPlusOne).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Game, SolverPipeline, Technique};
+
+    // A puzzle from the rating fixtures with a BUG+1 on the way to its solution
+    const PUZZLE: &str =
+        "..2...4.3.........9.6.73..5..1.9.5...7.8....6.69..781..9.2...8.2..681..........5.";
+
+    // Solves with every technique until the BUG+1 pattern shows up.
+    fn endgame() -> Game {
+        let mut game = PUZZLE.parse::<Game>().unwrap();
+        while game.find_bug_plus_one().is_none() {
+            let step = Technique::ALL
+                .iter()
+                .find_map(|&t| game.find_step(t))
+                .expect("no BUG+1 on the way");
+            game.apply_step(&step);
+        }
+        game
+    }
+
+    fn bug_plus_one() -> SolverPipeline {
+        SolverPipeline::from_names("bug-plus-one").unwrap()
+    }
+
+    #[test]
+    fn resolves_the_endgame() {
+        let mut game = endgame();
+        let counts = (0..81)
+            .map(Game::coords)
+            .map(|(y, x)| game.poss_mask(y, x).count_ones())
+            .filter(|&n| n > 0)
+            .collect::<Vec<_>>();
+        assert_eq!(counts.iter().filter(|&&n| n == 3).count(), 1);
+        assert!(counts.iter().all(|&n| n == 2 || n == 3));
+
+        let mut solution = game;
+        solution.solve().unwrap();
+        let steps = game.solve_with_pipeline(&bug_plus_one().assume_unique(true));
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].technique, Technique::BugPlusOne);
+        let (y, x, cv) = steps[0].placements[0];
+        assert_eq!(solution.board[y][x], Some(cv));
+        game.solve_with_pipeline(&SolverPipeline::default().assume_unique(true));
+        assert_eq!(game.to_array(), solution.to_array());
+    }
+
+    #[test]
+    fn skipped_unless_assuming_uniqueness() {
+        let mut game = endgame();
+        let before = game.to_array();
+        assert!(game.solve_with_pipeline(&bug_plus_one()).is_empty());
+        assert_eq!(game.to_array(), before);
+
+        let mut game = PUZZLE.parse::<Game>().unwrap();
+        let steps = game.solve_logical();
+        assert!(steps
+            .iter()
+            .all(|step| step.technique != Technique::BugPlusOne));
+        let mut game = PUZZLE.parse::<Game>().unwrap();
+        let steps = game.solve_with_pipeline(&SolverPipeline::default().assume_unique(true));
+        assert!(steps
+            .iter()
+            .any(|step| step.technique == Technique::BugPlusOne));
+        assert!(game.solved());
+    }
+}
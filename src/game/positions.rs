@@ -0,0 +1,197 @@
+// Where each value can still go within each row, column, and 3x3: the dual of each cell's
+// candidates, and what hidden subsets and fish are found from.
+
+use super::logic::unit_cells;
+use super::{CellValue, Coord, DigitArray, Game};
+use std::fmt;
+
+/// A set of positions within a row, column, or 3x3, stored as a bitmask with bit `i` for the
+/// `i`th cell. Rows count cells left to right, columns top to bottom, and 3x3s left to right and
+/// then top to bottom.
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
+pub struct PositionMask(u16);
+
+impl PositionMask {
+    pub fn empty() -> Self {
+        PositionMask(0)
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(self, i: usize) -> bool {
+        self.0 & 1 << i != 0
+    }
+
+    pub fn insert(&mut self, i: usize) {
+        self.0 |= 1 << i;
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The positions in the set, in order.
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        (0..9).filter(move |&i| self.contains(i))
+    }
+
+    /// The coordinates of the positions in the set, given which unit they're positions in. Units
+    /// are numbered with rows as 0-8, columns as 9-17, and 3x3s as 18-26.
+    pub fn cells(self, unit: usize) -> impl Iterator<Item = Coord> {
+        unit_cells(unit)
+            .enumerate()
+            .filter(move |&(i, _)| self.contains(i))
+            .map(|(_, cell)| cell)
+    }
+}
+
+impl fmt::Debug for PositionMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Where one value stands on the board, from `Game::digit_summary`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DigitSummary {
+    pub value: CellValue,
+    /// How many cells hold the value
+    pub placed: usize,
+    /// The rows, columns, and 3x3s that don't have the value yet. 3x3s are numbered left to right
+    /// and then top to bottom.
+    pub rows_missing: Vec<usize>,
+    pub cols_missing: Vec<usize>,
+    pub boxes_missing: Vec<usize>,
+    /// The empty cells where the value is still a candidate, in reading order
+    pub candidates: Vec<Coord>,
+}
+
+impl Game {
+    /// Sums up where a value has been placed and where it can still go.
+    pub fn digit_summary(&self, value: CellValue) -> DigitSummary {
+        let i = usize::from(value);
+        let missing =
+            |flags: &[DigitArray<bool>; 9]| (0..9).filter(|&j| !flags[j][value]).collect();
+        DigitSummary {
+            value,
+            placed: self
+                .iter_cells()
+                .filter(|&(_, _, &cell)| cell == Some(value))
+                .count(),
+            rows_missing: missing(&self.rows_flags),
+            cols_missing: missing(&self.cols_flags),
+            boxes_missing: missing(&self.sqrs_flags),
+            candidates: (0..9)
+                .flat_map(|y| self.digit_positions(y, i).cells(y))
+                .collect(),
+        }
+    }
+
+    /// The empty cells in row `y` where the value can still go.
+    pub fn digit_positions_in_row(&self, y: usize, value: CellValue) -> PositionMask {
+        self.digit_positions(y, usize::from(value))
+    }
+
+    /// The empty cells in column `x` where the value can still go.
+    pub fn digit_positions_in_col(&self, x: usize, value: CellValue) -> PositionMask {
+        self.digit_positions(9 + x, usize::from(value))
+    }
+
+    /// The empty cells in 3x3 `b` where the value can still go. The 3x3s are numbered left to
+    /// right and then top to bottom.
+    pub fn digit_positions_in_box(&self, b: usize, value: CellValue) -> PositionMask {
+        self.digit_positions(18 + b, usize::from(value))
+    }
+
+    // The empty cells where the value at index `i` can still go in a unit.
+    pub(crate) fn digit_positions(&self, unit: usize, i: usize) -> PositionMask {
+        PositionMask(self.positions[unit][i])
+    }
+
+    /// For every unit and value, the empty cells in the unit where the value can still go,
+    /// indexed as `[unit][value - 1]`. Units are numbered with rows as 0-8, columns as 9-17, and
+    /// 3x3s as 18-26.
+    pub fn value_positions_in_units(&self) -> [[PositionMask; 9]; 27] {
+        let mut positions = [[PositionMask::empty(); 9]; 27];
+        for (unit, values) in positions.iter_mut().enumerate() {
+            for (i, mask) in values.iter_mut().enumerate() {
+                *mask = self.digit_positions(unit, i);
+            }
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::random_sweeps;
+    use crate::game::{SplitMix64, SudokuRng};
+    use crate::gameboards::EASY;
+
+    // Checks every mask against a scan of the cells' candidates.
+    fn assert_matches_scan(game: &Game) {
+        let scan = |cells: &mut dyn Iterator<Item = Coord>, value| {
+            let mut mask = PositionMask::empty();
+            for (j, (y, x)) in cells.enumerate() {
+                if game.board[y][x].is_none() && game.candidates((y, x)).contains(value) {
+                    mask.insert(j);
+                }
+            }
+            mask
+        };
+        for &value in &CellValue::ALL {
+            for k in 0..9 {
+                let row = scan(&mut (0..9).map(|x| (k, x)), value);
+                assert_eq!(game.digit_positions_in_row(k, value), row);
+                let col = scan(&mut (0..9).map(|y| (y, k)), value);
+                assert_eq!(game.digit_positions_in_col(k, value), col);
+                let (top, left) = (3 * (k / 3), 3 * (k % 3));
+                let mut cells = (0..9).map(|j| (top + j / 3, left + j % 3));
+                assert_eq!(
+                    game.digit_positions_in_box(k, value),
+                    scan(&mut cells, value)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn masks_match_a_scan_through_propagation() {
+        for seed in 0..100 {
+            random_sweeps(seed, assert_matches_scan);
+        }
+    }
+
+    #[test]
+    fn masks_match_a_scan_through_player_moves() {
+        let mut rng = SplitMix64::new(180);
+        let mut game = Game::new(EASY);
+        assert_matches_scan(&game);
+        for _ in 0..2000 {
+            let coord = Game::coords(rng.below(81));
+            let value = CellValue::from_index(rng.below(9));
+            match rng.below(4) {
+                0 => {
+                    let _ = game.erase(coord);
+                }
+                1 => {
+                    let _ = game.eliminate_candidate(coord, value);
+                }
+                2 => {
+                    let _ = game.restore_candidate(coord, value);
+                }
+                _ => {
+                    let _ = game.place(coord, value);
+                }
+            }
+            assert_matches_scan(&game);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// Wrappers that tell a puzzle from a solution in the types, for code that passes both around and
+// wants the compiler to catch them getting swapped. `Game` stays the type everything else takes.
+
+use super::{BoardError, Game, ParseError, SolveError};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A game that's been checked not to break the rules: no value repeats in a row, column, or 3x3,
+/// and every empty cell has a candidate. It can have any number of solutions, including none.
+#[derive(Copy, Clone)]
+pub struct Puzzle(Game);
+
+/// A full board that doesn't break the rules.
+#[derive(Copy, Clone)]
+pub struct Solution(Game);
+
+impl Puzzle {
+    /// Checks a game and wraps it, failing with `BoardError::Invalid` if it breaks the rules.
+    pub fn new(game: Game) -> Result<Self, BoardError> {
+        if game.is_valid(false) {
+            Ok(Puzzle(game))
+        } else {
+            Err(BoardError::Invalid)
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.0
+    }
+
+    pub fn into_game(self) -> Game {
+        self.0
+    }
+
+    /// Solves a copy of the puzzle, the same way as `Game::solve`.
+    pub fn solve(&self) -> Result<Solution, SolveError> {
+        let mut game = self.0;
+        game.solve()?;
+        Ok(Solution(game))
+    }
+}
+
+impl Solution {
+    /// Checks a game and wraps it, failing with `BoardError::Incomplete` if it has empty cells or
+    /// `BoardError::Invalid` if it breaks the rules.
+    pub fn new(game: Game) -> Result<Self, BoardError> {
+        if !game.is_valid(false) {
+            Err(BoardError::Invalid)
+        } else if !game.solved() {
+            Err(BoardError::Incomplete)
+        } else {
+            Ok(Solution(game))
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.0
+    }
+
+    pub fn into_game(self) -> Game {
+        self.0
+    }
+
+    /// Whether this is a solution of the puzzle: every cell filled in the puzzle has the same
+    /// value here.
+    pub fn conforms_to(&self, puzzle: &Puzzle) -> bool {
+        puzzle
+            .0
+            .iter_cells()
+            .all(|(y, x, cell)| cell.is_none() || *cell == self.0.board[y][x])
+    }
+}
+
+impl TryFrom<Game> for Puzzle {
+    type Error = BoardError;
+
+    fn try_from(game: Game) -> Result<Self, Self::Error> {
+        Puzzle::new(game)
+    }
+}
+
+impl TryFrom<Game> for Solution {
+    type Error = BoardError;
+
+    fn try_from(game: Game) -> Result<Self, Self::Error> {
+        Solution::new(game)
+    }
+}
+
+impl From<Puzzle> for Game {
+    fn from(puzzle: Puzzle) -> Self {
+        puzzle.0
+    }
+}
+
+impl From<Solution> for Game {
+    fn from(solution: Solution) -> Self {
+        solution.0
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = ParseError;
+
+    /// Reads a puzzle the same way as a `Game`, which already turns down boards that break the
+    /// rules.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Puzzle::new(s.parse()?)?)
+    }
+}
+
+impl FromStr for Solution {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Solution::new(s.parse()?)?)
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -0,0 +1,86 @@
+// A small, fast, seedable random number generator, so that randomized methods are reproducible
+// without pulling in a dependency, and the trait that lets callers bring their own.
+
+/// A source of random numbers for the randomized methods. `SplitMix64` is built in, and with the
+/// `rand` feature every `rand_core::RngCore` is one too.
+pub trait SudokuRng {
+    fn next_u64(&mut self) -> u64;
+
+    /// A number in `0..n`. The modulo bias is negligible for the small `n` used here.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// SplitMix64, as described by Steele, Lea, and Flood in "Fast Splittable Pseudorandom Number
+/// Generators". The sequence a given seed produces is part of this crate's stable interface, so a
+/// seed gives the same results on every platform and in every release.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64(seed)
+    }
+}
+
+impl SudokuRng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(GAMMA);
+        mix(self.0)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R: rand_core::RngCore> SudokuRng for R {
+    fn next_u64(&mut self) -> u64 {
+        rand_core::RngCore::next_u64(self)
+    }
+}
+
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+const fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SplitMix64, SudokuRng};
+
+    // The reference implementation's first outputs, so the sequence can't change by accident.
+    #[test]
+    fn matches_the_reference_outputs() {
+        let mut rng = SplitMix64::new(0);
+        let first = (0..3).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            first,
+            [
+                0xe220_a839_7b1d_cdaf,
+                0x6e78_9e6a_a1b9_65f4,
+                0x06c4_5d18_8009_454f
+            ]
+        );
+        let mut rng = SplitMix64::new(1_234_567);
+        let first = (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            first,
+            [
+                6_457_827_717_110_365_317,
+                3_203_168_211_198_807_973,
+                9_817_491_932_198_370_423,
+                4_593_380_528_125_082_431,
+                16_408_922_859_458_223_821,
+            ]
+        );
+    }
+
+    #[test]
+    fn below_is_pinned_too() {
+        let mut rng = SplitMix64::new(42);
+        let picks = (0..10).map(|_| rng.below(9)).collect::<Vec<_>>();
+        assert_eq!(picks, [1, 1, 0, 0, 7, 6, 1, 5, 1, 2]);
+    }
+}
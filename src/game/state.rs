@@ -0,0 +1,282 @@
+// Saving a partially-solved game to pick up again later: exact snapshots of the solver's state, so
+// nothing has to be recomputed, and plainer saves of just what a player did, for UIs.
+
+use super::{BoardError, Candidates, CellValue, Game};
+
+const VERSION: u8 = 3;
+// Version byte, the board, the possibility, elimination, and crossed off masks, the three flag
+// arrays, and which cells are givens, a row at a time
+const LEN: usize = 1 + 81 + 3 * 2 * 81 + 3 * 2 * 9 + 2 * 9;
+
+/// A game's complete solver state as bytes: the board, every cell's possible values, the values
+/// crossed off by logical techniques and by the player, the row, column, and 3x3 flags, and which
+/// cells are givens.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateBlob(Vec<u8>);
+
+impl StateBlob {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for StateBlob {
+    fn from(bytes: Vec<u8>) -> Self {
+        StateBlob(bytes)
+    }
+}
+
+fn to_mask(bools: &[bool; 9]) -> u16 {
+    bools
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b)
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+fn from_mask(mask: u16) -> [bool; 9] {
+    let mut bools = [false; 9];
+    for (i, b) in bools.iter_mut().enumerate() {
+        *b = mask & 1 << i != 0;
+    }
+    bools
+}
+
+// Reads little endian masks, rejecting any with bits set past the ninth value.
+fn read_masks(bytes: &[u8]) -> Result<Vec<[bool; 9]>, BoardError> {
+    bytes
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .map(|mask| {
+            if mask >> 9 == 0 {
+                Ok(from_mask(mask))
+            } else {
+                Err(BoardError::CorruptState)
+            }
+        })
+        .collect()
+}
+
+impl Game {
+    /// Captures the game's full solver state, including everything derived from the givens, so
+    /// that `load_state` can restore it exactly.
+    pub fn dump_state(&self) -> StateBlob {
+        let mut bytes = Vec::with_capacity(LEN);
+        bytes.push(VERSION);
+        bytes.extend(
+            self.board
+                .iter()
+                .flatten()
+                .map(|cell| cell.map_or(0, |cv| cv as u8)),
+        );
+        for poss in self.cell_poss.iter().flatten() {
+            bytes.extend_from_slice(&to_mask(poss).to_le_bytes());
+        }
+        for masks in [&self.elims, &self.crossed_off].iter() {
+            for values in masks.iter().flatten() {
+                bytes.extend_from_slice(&values.bits().to_le_bytes());
+            }
+        }
+        for flags in [&self.rows_flags, &self.cols_flags, &self.sqrs_flags].iter() {
+            for group in flags.iter() {
+                bytes.extend_from_slice(&to_mask(group).to_le_bytes());
+            }
+        }
+        for row in self.givens.iter() {
+            bytes.extend_from_slice(&to_mask(row).to_le_bytes());
+        }
+        StateBlob(bytes)
+    }
+
+    /// Restores a game saved with `dump_state`. The blob is checked for internal consistency: the
+    /// flags and possible values have to be exactly what the board and eliminations imply.
+    pub fn load_state(blob: &StateBlob) -> Result<Game, BoardError> {
+        let bytes = blob.as_bytes();
+        if bytes.len() != LEN || bytes[0] != VERSION {
+            return Err(BoardError::CorruptState);
+        }
+        let mut game = Game::new([[0; 9]; 9]);
+        for (i, &n) in bytes[1..82].iter().enumerate() {
+            let (y, x) = Game::coords(i);
+            match n {
+                0 => {}
+                n if n <= 9 => {
+                    game.set_cell(y, x, CellValue::new(n).unwrap());
+                }
+                value => {
+                    return Err(BoardError::InvalidValue {
+                        row: y,
+                        col: x,
+                        value,
+                    })
+                }
+            }
+        }
+        let poss = read_masks(&bytes[82..244])?;
+        let elims = read_masks(&bytes[244..406])?;
+        let crossed_off = read_masks(&bytes[406..568])?;
+        let flags = read_masks(&bytes[568..622])?;
+        let givens = read_masks(&bytes[622..])?;
+        for (i, (elim, crossed)) in elims.into_iter().zip(crossed_off).enumerate() {
+            game.elims[i / 9][i % 9] = Candidates::from_bools(&elim);
+            game.crossed_off[i / 9][i % 9] = Candidates::from_bools(&crossed);
+        }
+        for (y, row) in givens.iter().enumerate() {
+            for (x, &given) in row.iter().enumerate() {
+                // Only a filled cell can be a given.
+                if given && game.board[y][x].is_none() {
+                    return Err(BoardError::CorruptState);
+                }
+                game.givens[y][x] = given;
+            }
+        }
+        game.reset_poss();
+        // Recomputing from the board and eliminations has to land on exactly the saved state.
+        let consistent = game.cell_poss.iter().flatten().eq(poss.iter())
+            && game.rows_flags.iter().eq(flags[0..9].iter())
+            && game.cols_flags.iter().eq(flags[9..18].iter())
+            && game.sqrs_flags.iter().eq(flags[18..27].iter());
+        if !consistent {
+            Err(BoardError::CorruptState)
+        } else if !game.is_valid(false) {
+            Err(BoardError::Invalid)
+        } else {
+            Ok(game)
+        }
+    }
+
+    /// Saves what a player has done to the game: the givens, the cells filled in since, and the
+    /// candidates crossed off.
+    pub fn to_save(&self) -> SaveState {
+        let mut save = SaveState {
+            givens: [[0; 9]; 9],
+            filled: [[0; 9]; 9],
+            pencil_marks: [[Candidates::empty(); 9]; 9],
+        };
+        for (y, x, cell) in self.iter_cells() {
+            let n = cell.map_or(0, |cv| cv as u8);
+            if self.givens[y][x] {
+                save.givens[y][x] = n;
+            } else {
+                save.filled[y][x] = n;
+            }
+            save.pencil_marks[y][x] = self.crossed_off[y][x];
+        }
+        save
+    }
+
+    /// Picks up a game saved with `to_save`. The givens are checked the same way as
+    /// `Game::try_new`, and the filled cells can't repeat a value or sit on a given. Anything the
+    /// logical techniques had crossed off is worked out again as needed rather than restored.
+    pub fn from_save(save: &SaveState) -> Result<Game, BoardError> {
+        let mut game = Game::try_new(save.givens)?;
+        for (y, row) in save.filled.iter().enumerate() {
+            for (x, &n) in row.iter().enumerate() {
+                let cv = match n {
+                    0 => continue,
+                    n if n <= 9 => CellValue::new(n).unwrap(),
+                    value => {
+                        return Err(BoardError::InvalidValue {
+                            row: y,
+                            col: x,
+                            value,
+                        })
+                    }
+                };
+                let s = game.sqrs_ind(y, x);
+                if game.board[y][x].is_some()
+                    || game.rows_flags[y][cv]
+                    || game.cols_flags[x][cv]
+                    || game.sqrs_flags[s][cv]
+                {
+                    return Err(BoardError::Invalid);
+                }
+                game.set_cell(y, x, cv);
+            }
+        }
+        for (y, x) in (0..81).map(Game::coords) {
+            game.crossed_off[y][x] = save.pencil_marks[y][x];
+        }
+        game.reset_poss();
+        Ok(game)
+    }
+}
+
+/// What a player has done to a game, from `Game::to_save`, for a UI to keep however it likes and
+/// pick the game up from later. Unlike a `StateBlob`, this leaves out everything the solver can
+/// work out again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SaveState {
+    /// The givens the game was made with, row by row, with 0 for every other cell
+    pub givens: [[u8; 9]; 9],
+    /// Values filled in since, by the player or the solver, with 0 for empty cells and givens
+    pub filled: [[u8; 9]; 9],
+    /// The candidates the player has crossed off in each cell, which is how pencil marks are kept
+    pub pencil_marks: [[Candidates; 9]; 9],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StateBlob, VERSION};
+    use crate::game::{BoardError, CellValue, Game, Technique};
+    use crate::gameboards::{TOP95_1, TOP95_1_SOLUTION};
+
+    // TOP95_1 partway through: a few moves by the logical solver, one value filled in by the
+    // player, and one wrong candidate crossed off by them.
+    fn partway() -> Game {
+        let mut game = Game::new(TOP95_1);
+        for _ in 0..5 {
+            game.apply_technique(Technique::HiddenSingle);
+            game.apply_technique(Technique::LockedCandidates);
+        }
+        let empty = (0..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| game.board[y][x].is_none())
+            .collect::<Vec<_>>();
+        let (y, x) = empty[0];
+        let right = CellValue::new(TOP95_1_SOLUTION[y][x]).unwrap();
+        game.place((y, x), right).unwrap();
+        let (y, x) = empty[1];
+        let wrong = game
+            .candidates((y, x))
+            .iter()
+            .find(|&cv| cv as u8 != TOP95_1_SOLUTION[y][x])
+            .unwrap();
+        game.eliminate_candidate((y, x), wrong).unwrap();
+        game
+    }
+
+    #[test]
+    fn state_round_trips_exactly() {
+        let game = partway();
+        assert!(game.elims.iter().flatten().any(|elims| !elims.is_empty()));
+        assert!(game.givens.iter().flatten().any(|&given| given));
+        let blob = game.dump_state();
+        let loaded = Game::load_state(&blob).unwrap();
+        assert_eq!(loaded.board, game.board);
+        assert_eq!(loaded.givens, game.givens);
+        assert_eq!(loaded.elims, game.elims);
+        assert_eq!(loaded.crossed_off, game.crossed_off);
+        assert_eq!(loaded.cell_poss, game.cell_poss);
+        assert_eq!(loaded.positions, game.positions);
+        assert_eq!(loaded.dump_state(), blob);
+    }
+
+    #[test]
+    fn damaged_state_is_rejected() {
+        let bytes = partway().dump_state().as_bytes().to_vec();
+        let load = |bytes: Vec<u8>| Game::load_state(&StateBlob::from(bytes)).err();
+        assert_eq!(
+            load(bytes[..bytes.len() - 1].to_vec()),
+            Some(BoardError::CorruptState)
+        );
+        let mut version = bytes.clone();
+        version[0] = VERSION + 1;
+        assert_eq!(load(version), Some(BoardError::CorruptState));
+        // A possibility the board and eliminations don't account for.
+        let mut poss = bytes;
+        let i = (0..81).find(|&i| poss[1 + i] == 0).unwrap();
+        poss[82 + 2 * i] ^= 0xff;
+        assert_eq!(load(poss), Some(BoardError::CorruptState));
+    }
+}
@@ -0,0 +1,262 @@
+// Conversions between games and text: the common one-line format with a character per cell, Rust
+// source for adding boards to `gameboards`, TikZ for LaTeX, the boxed grid `Display` draws, and
+// plain text views of the solver's state.
+
+use super::{BoardError, CellValue, Game};
+use crate::io::{normalize, NormalizeError};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Reasons a line of text can't be read as a game.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The line didn't have exactly 81 cells
+    Length(usize),
+    /// A character that isn't a digit, a blank marker, or anything `io::normalize` skips. The
+    /// index counts characters from the start of the text.
+    InvalidChar { index: usize, ch: char },
+    /// The cells were readable but don't make a valid board
+    Board(BoardError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Length(len) => write!(f, "expected 81 cells, found {}", len),
+            ParseError::InvalidChar { index, ch } => {
+                write!(f, "invalid character {:?} at position {}", ch, index)
+            }
+            ParseError::Board(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<BoardError> for ParseError {
+    fn from(other: BoardError) -> Self {
+        ParseError::Board(other)
+    }
+}
+
+/// Checks that a line could be a game, without building one: exactly 81 characters, each `1`-`9`
+/// for a filled cell or `0` or `.` for an empty one, with no value repeated in a row, column, or
+/// 3x3. Trailing whitespace is ignored, but nothing else that `io::normalize` would clean up is,
+/// so this is for scanning files already in the one-line format. It's much cheaper than parsing
+/// a `Game` for throwing out bad lines in bulk, but a line that passes can still fail to parse if
+/// the givens leave some empty cell with no possible value.
+pub fn quick_validate(line: &str) -> Result<(), ParseError> {
+    let line = line.trim_end();
+    let len = line.chars().count();
+    if len != 81 {
+        return Err(ParseError::Length(len));
+    }
+    let (mut rows, mut cols, mut sqrs) = ([0u16; 9], [0u16; 9], [0u16; 9]);
+    for (index, ch) in line.chars().enumerate() {
+        let bit = match ch {
+            '0' | '.' => continue,
+            '1'..='9' => 1 << usize::from(CellValue::from_char(ch).unwrap()),
+            _ => return Err(ParseError::InvalidChar { index, ch }),
+        };
+        let (y, x) = Game::coords(index);
+        let sqr = y / 3 * 3 + x / 3;
+        if (rows[y] | cols[x] | sqrs[sqr]) & bit != 0 {
+            return Err(ParseError::Board(BoardError::Invalid));
+        }
+        rows[y] |= bit;
+        cols[x] |= bit;
+        sqrs[sqr] |= bit;
+    }
+    Ok(())
+}
+
+impl FromStr for Game {
+    type Err = ParseError;
+
+    /// Reads a game from 81 cells, row by row, with `1`-`9` for filled cells and `0` or `.` for
+    /// empty ones. The text goes through `io::normalize` first, so pasted puzzles with other blank
+    /// markers, full-width digits, grid lines, or stray whitespace read fine.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = normalize(s).map_err(|e| match e {
+            NormalizeError::Length(len) => ParseError::Length(len),
+            NormalizeError::Unrecognized { line, column, ch } => {
+                let before = s
+                    .split('\n')
+                    .take(line - 1)
+                    .map(|text| text.chars().count() + 1)
+                    .sum::<usize>();
+                ParseError::InvalidChar {
+                    index: before + column - 1,
+                    ch,
+                }
+            }
+        })?;
+        let mut numbers = [[0; 9]; 9];
+        for (index, ch) in line.chars().enumerate() {
+            numbers[index / 9][index % 9] = CellValue::from_char(ch).map_or(0, |cv| cv as u8);
+        }
+        Ok(Game::try_new(numbers)?)
+    }
+}
+
+/// The board drawn as a grid with box-drawing characters, from `Game::display`. This is also what
+/// formatting a `Game` with `{}` gives.
+#[derive(Copy, Clone)]
+pub struct BoardDisplay<'a> {
+    game: &'a Game,
+    highlight: Option<CellValue>,
+}
+
+impl BoardDisplay<'_> {
+    /// Picks out one value, the way a player scans the board for a single digit: cells holding it
+    /// show it, empty cells where it's still a candidate show `*`, other filled cells show `·`,
+    /// and everything else is left blank.
+    pub fn highlight_digit(mut self, value: CellValue) -> Self {
+        self.highlight = Some(value);
+        self
+    }
+
+    fn cell_char(&self, y: usize, x: usize) -> char {
+        let cell = self.game.board[y][x];
+        match self.highlight {
+            None => cell.map_or(' ', CellValue::to_char),
+            Some(value) => match cell {
+                Some(cv) if cv == value => cv.to_char(),
+                Some(_) => '·',
+                None if self.game.cell_poss[y][x][usize::from(value)] => '*',
+                None => ' ',
+            },
+        }
+    }
+}
+
+impl fmt::Display for BoardDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_grid(f, |y, x| (self.cell_char(y, x), false))
+    }
+}
+
+// Draws a grid with box-drawing characters, asking `cell` for what goes in each cell and whether
+// to mark it, which puts it in brackets.
+pub(super) fn write_grid(
+    f: &mut fmt::Formatter,
+    cell: impl Fn(usize, usize) -> (char, bool),
+) -> fmt::Result {
+    writeln!(f, "┌───┬───┬───╥───┬───┬───╥───┬───┬───┐")?;
+    for y in 0..9 {
+        write!(f, "│")?;
+        for x in 0..9 {
+            let wall = if x == 2 || x == 5 { '║' } else { '│' };
+            match cell(y, x) {
+                (ch, false) => write!(f, " {} {}", ch, wall)?,
+                (ch, true) => write!(f, "[{}]{}", ch, wall)?,
+            }
+        }
+        writeln!(f)?;
+        match y {
+            8 => writeln!(f, "└───┴───┴───╨───┴───┴───╨───┴───┴───┘")?,
+            2 | 5 => writeln!(f, "╞═══╪═══╪═══╬═══╪═══╪═══╬═══╪═══╪═══╡")?,
+            _ => writeln!(f, "├───┼───┼───╫───┼───┼───╫───┼───┼───┤")?,
+        }
+    }
+    Ok(())
+}
+
+impl Game {
+    /// The board as a grid, with options for how to draw it.
+    pub fn display(&self) -> BoardDisplay<'_> {
+        BoardDisplay {
+            game: self,
+            highlight: None,
+        }
+    }
+
+    /// Writes the game as a single line of 81 cells, row by row, with `.` for empty cells.
+    pub fn to_line(&self) -> String {
+        self.board
+            .iter()
+            .flatten()
+            .map(|cell| cell.map_or('.', CellValue::to_char))
+            .collect()
+    }
+
+    /// Draws each cell's candidate count as a 9x9 grid, with `·` for filled cells, to show at a
+    /// glance where the board is most and least constrained.
+    pub fn heatmap(&self) -> String {
+        let counts = self.candidate_counts();
+        let mut out = String::new();
+        for (y, row) in counts.iter().enumerate() {
+            if y == 3 || y == 6 {
+                out.push_str("------+-------+------\n");
+            }
+            for (x, &count) in row.iter().enumerate() {
+                if x == 3 || x == 6 {
+                    out.push_str(" |");
+                }
+                if x > 0 {
+                    out.push(' ');
+                }
+                match self.board[y][x] {
+                    Some(_) => out.push('·'),
+                    None => out.push_str(&count.to_string()),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the board as a TikZ picture for LaTeX documents, with thick lines around the 3x3s
+    /// and each filled cell's value centered in it. Needs `\usepackage{tikz}`.
+    #[cfg(feature = "tikz")]
+    pub fn to_tikz(&self) -> String {
+        let mut out = String::from("\\begin{tikzpicture}[scale=0.6]\n");
+        out.push_str("  \\draw[step=1, thin] (0, 0) grid (9, 9);\n");
+        out.push_str("  \\draw[step=3, very thick] (0, 0) grid (9, 9);\n");
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(cv) = cell {
+                    // TikZ counts y upwards, so the top row sits at the top of the picture.
+                    out.push_str(&format!(
+                        "  \\node at ({}.5, {}.5) {{{}}};\n",
+                        x,
+                        8 - y,
+                        *cv as u8
+                    ));
+                }
+            }
+        }
+        out.push_str("\\end{tikzpicture}\n");
+        out
+    }
+
+    /// Writes the game as a `pub const` in the style of `gameboards`, ready to paste in as a new
+    /// board. The name is uppercased, and anything that can't go in an identifier is replaced with
+    /// an underscore.
+    pub fn to_rust_const(&self, name: &str) -> String {
+        let mut ident = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+            ident.insert(0, '_');
+        }
+        let mut out = format!("pub const {}: [[u8; 9]; 9] = [\n", ident);
+        for row in self.board.iter() {
+            let cells = row
+                .iter()
+                .map(|cell| cell.map_or(0, |cv| cv as u8).to_string())
+                .collect::<Vec<_>>();
+            out.push_str(&format!("    [{}],\n", cells.join(", ")));
+        }
+        out.push_str("];\n");
+        out
+    }
+}
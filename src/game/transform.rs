@@ -0,0 +1,375 @@
+// Rearranging the board in ways that keep it valid, for making puzzles that play the same but look
+// different, and spotting puzzles that are rearrangements of each other.
+
+use super::{CellValue, Coord, DigitArray, Game};
+
+/// A rearrangement of the board that keeps every valid board valid: an optional transpose, then a
+/// reordering of the rows and columns that keeps bands and stacks together, then a relabeling of
+/// the values.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Transform {
+    /// Whether to swap rows with columns first
+    pub transpose: bool,
+    /// The row, after any transpose, that ends up at each position
+    pub rows: [usize; 9],
+    /// The column, after any transpose, that ends up at each position
+    pub cols: [usize; 9],
+    /// What each value becomes, indexed by the old value minus 1
+    pub values: [CellValue; 9],
+}
+
+impl Transform {
+    /// The transform that leaves everything where it is.
+    pub fn identity() -> Self {
+        Transform {
+            transpose: false,
+            rows: [0, 1, 2, 3, 4, 5, 6, 7, 8],
+            cols: [0, 1, 2, 3, 4, 5, 6, 7, 8],
+            values: CellValue::ALL,
+        }
+    }
+}
+
+impl Game {
+    /// Reorders the three horizontal bands of 3x3s, carrying their rows along, so that the band at
+    /// position `i` afterwards is the one that was at `band_order[i]`. Candidates the solver or
+    /// player has crossed off move with their cells.
+    ///
+    /// Panics if `band_order` isn't an ordering of 0, 1, and 2.
+    pub fn permute_bands(&mut self, band_order: [usize; 3]) {
+        assert_order(band_order);
+        self.move_cells(|(y, x)| (3 * band_order[y / 3] + y % 3, x));
+    }
+
+    /// Reorders the three vertical stacks of 3x3s, carrying their columns along, so that the stack
+    /// at position `i` afterwards is the one that was at `stack_order[i]`.
+    ///
+    /// Panics if `stack_order` isn't an ordering of 0, 1, and 2.
+    pub fn permute_stacks(&mut self, stack_order: [usize; 3]) {
+        assert_order(stack_order);
+        self.move_cells(|(y, x)| (y, 3 * stack_order[x / 3] + x % 3));
+    }
+
+    /// Applies a transform to a copy of the game. Candidates the solver or player has crossed off
+    /// move and get relabeled along with their cells.
+    ///
+    /// Panics if the rows or columns split up a band or stack or repeat a line, or if two values
+    /// become the same value.
+    pub fn transformed(&self, transform: &Transform) -> Game {
+        assert_lines(transform.rows);
+        assert_lines(transform.cols);
+        let Transform {
+            transpose,
+            rows,
+            cols,
+            values,
+        } = *transform;
+        assert!(
+            (0..9).all(|i| (0..i).all(|j| values[i] != values[j])),
+            "{:?} maps two values to the same value",
+            values
+        );
+        let mut game = *self;
+        for cell in game.board.iter_mut().flatten() {
+            *cell = cell.map(|cv| values[usize::from(cv)]);
+        }
+        for mask in game.cell_poss.iter_mut().flatten() {
+            let old = *mask;
+            for i in 0..9 {
+                mask[usize::from(values[i])] = old[i];
+            }
+        }
+        for crossed in game.elims.iter_mut().chain(&mut game.crossed_off).flatten() {
+            *crossed = crossed.iter().map(|cv| values[usize::from(cv)]).collect();
+        }
+        #[cfg(feature = "paranoid")]
+        if let Some(solution) = &mut game.oracle {
+            for n in solution.iter_mut().flatten() {
+                *n = values[usize::from(*n) - 1] as u8;
+            }
+        }
+        game.move_cells(|(y, x)| match transpose {
+            false => (rows[y], cols[x]),
+            true => (cols[x], rows[y]),
+        });
+        game
+    }
+
+    /// Every transform that turns the board back into itself, the identity included. Only the
+    /// filled cells have to match. Values that don't appear on the board could be relabeled any
+    /// which way, so only the transforms that keep them in the same order are listed. A board
+    /// with few clues can have a great many automorphisms; the empty board has millions.
+    pub fn automorphisms(&self) -> Vec<Transform> {
+        let target = self.to_array();
+        let mut transposed = [[0; 9]; 9];
+        for (y, x) in (0..81).map(Game::coords) {
+            transposed[x][y] = target[y][x];
+        }
+        let mut found = Vec::new();
+        for (transpose, source) in [(false, target), (true, transposed)] {
+            for cols in line_orders() {
+                let mut search = AutomorphismSearch {
+                    source: &source,
+                    target: &target,
+                    transpose,
+                    cols,
+                    found: &mut found,
+                };
+                search.place_rows(0, &mut [0; 9], [0; 10]);
+            }
+        }
+        found
+    }
+
+    /// The board in a standard form shared by every rearrangement of it. Two games have the same
+    /// canonical form exactly when one can be turned into the other by transposing, reordering
+    /// bands and stacks, reordering rows within bands and columns within stacks, and swapping
+    /// values around. Out of all those rearrangements, this is the one that reads smallest row by
+    /// row, with values renumbered in the order they first appear and 0 for an empty cell. Only the
+    /// filled cells count, not candidates.
+    pub fn canonical_form(&self) -> [[u8; 9]; 9] {
+        let grid = self.to_array();
+        let mut transposed = [[0; 9]; 9];
+        for (y, x) in (0..81).map(Game::coords) {
+            transposed[x][y] = grid[y][x];
+        }
+        // Larger than any value, so the first arrangement tried replaces it.
+        let mut best = [[10; 9]; 9];
+        for grid in [grid, transposed] {
+            for cols in line_orders() {
+                let mut search = CanonicalSearch {
+                    grid: &grid,
+                    cols,
+                    best: &mut best,
+                };
+                search.place_rows(0, &mut [0; 9], [0; 10], 1);
+            }
+        }
+        best
+    }
+
+    /// A 64-bit hash of `canonical_form`, so rearrangements of the same game get the same
+    /// fingerprint. Different games can collide, so compare canonical forms to be sure.
+    pub fn fingerprint(&self) -> u64 {
+        hash_form(&self.canonical_form())
+    }
+
+    // Rearranges the cells so each one takes on everything about the cell `source` gives for it,
+    // then rebuilds the flags to match.
+    fn move_cells(&mut self, source: impl Fn(Coord) -> Coord) {
+        let old = *self;
+        for (y, x) in (0..81).map(Game::coords) {
+            let (sy, sx) = source((y, x));
+            self.board[y][x] = old.board[sy][sx];
+            self.cell_poss[y][x] = old.cell_poss[sy][sx];
+            self.elims[y][x] = old.elims[sy][sx];
+            self.crossed_off[y][x] = old.crossed_off[sy][sx];
+            self.givens[y][x] = old.givens[sy][sx];
+        }
+        #[cfg(feature = "paranoid")]
+        if let (Some(old), Some(solution)) = (old.oracle, &mut self.oracle) {
+            for (y, x) in (0..81).map(Game::coords) {
+                let (sy, sx) = source((y, x));
+                solution[y][x] = old[sy][sx];
+            }
+        }
+        self.rows_flags = [DigitArray([false; 9]); 9];
+        self.cols_flags = [DigitArray([false; 9]); 9];
+        self.sqrs_flags = [DigitArray([false; 9]); 9];
+        for (y, x) in (0..81).map(Game::coords) {
+            if let Some(cv) = self.board[y][x] {
+                self.rows_flags[y][cv] = true;
+                self.cols_flags[x][cv] = true;
+                self.sqrs_flags[3 * (y / 3) + x / 3][cv] = true;
+            }
+        }
+        self.sync_positions();
+        debug_assert!(self.flags_match_board(), "flags out of step with the board");
+    }
+}
+
+fn assert_order(order: [usize; 3]) {
+    let mut sorted = order;
+    sorted.sort_unstable();
+    assert!(
+        sorted == [0, 1, 2],
+        "{:?} isn't an ordering of 0, 1, and 2",
+        order
+    );
+}
+
+// The hash behind `Game::fingerprint`, for callers that already have the canonical form. It's
+// FNV-1a, which unlike the standard library's hasher is the same from one build to the next.
+pub(crate) fn hash_form(form: &[[u8; 9]; 9]) -> u64 {
+    form.iter()
+        .flatten()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &v| {
+            (hash ^ u64::from(v)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// Picks rows one at a time for `canonical_form`, with the column order already chosen, dropping any
+// ordering as soon as it reads larger than the best found so far.
+struct CanonicalSearch<'a> {
+    grid: &'a [[u8; 9]; 9],
+    cols: [usize; 9],
+    best: &'a mut [[u8; 9]; 9],
+}
+
+impl CanonicalSearch<'_> {
+    // `rows` holds the old row for each new row before `depth`, `labels` the new number for each
+    // value seen so far, and `next` the number for the next new value.
+    fn place_rows(&mut self, depth: usize, rows: &mut [usize; 9], labels: [u8; 10], next: u8) {
+        if depth == 9 {
+            return;
+        }
+        for row in next_rows(depth, rows) {
+            let mut labels = labels;
+            let mut next = next;
+            let mut line = [0; 9];
+            for (x, cell) in line.iter_mut().enumerate() {
+                let v = self.grid[row][self.cols[x]] as usize;
+                if v != 0 && labels[v] == 0 {
+                    labels[v] = next;
+                    next += 1;
+                }
+                *cell = labels[v];
+            }
+            if line > self.best[depth] {
+                continue;
+            }
+            if line < self.best[depth] {
+                // Whatever this ordering goes on to is the new best, so the rows of the old best
+                // below this one no longer mean anything.
+                self.best[depth] = line;
+                for later in &mut self.best[depth + 1..] {
+                    *later = [10; 9];
+                }
+            }
+            rows[depth] = row;
+            self.place_rows(depth + 1, rows, labels, next);
+        }
+    }
+}
+
+// Picks rows one at a time for `automorphisms`, with the column order already chosen, dropping any
+// ordering as soon as a row fails to line up with the original board.
+struct AutomorphismSearch<'a> {
+    source: &'a [[u8; 9]; 9],
+    target: &'a [[u8; 9]; 9],
+    transpose: bool,
+    cols: [usize; 9],
+    found: &'a mut Vec<Transform>,
+}
+
+impl AutomorphismSearch<'_> {
+    // `rows` holds the source row for each target row before `depth`, and `labels` what each
+    // value seen so far has to become.
+    fn place_rows(&mut self, depth: usize, rows: &mut [usize; 9], labels: [u8; 10]) {
+        if depth == 9 {
+            self.found.push(Transform {
+                transpose: self.transpose,
+                rows: *rows,
+                cols: self.cols,
+                values: fill_labels(labels),
+            });
+            return;
+        }
+        for row in next_rows(depth, rows) {
+            let mut labels = labels;
+            let lines_up = (0..9).all(|x| {
+                let (from, to) = (self.source[row][self.cols[x]], self.target[depth][x]);
+                match (from, to) {
+                    (0, 0) => true,
+                    (0, _) | (_, 0) => false,
+                    _ if labels[from as usize] == to => true,
+                    _ if labels[from as usize] == 0 && !labels.contains(&to) => {
+                        labels[from as usize] = to;
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            if lines_up {
+                rows[depth] = row;
+                self.place_rows(depth + 1, rows, labels);
+            }
+        }
+    }
+}
+
+// Turns what each value has to become, with 0 for values not seen, into a full relabeling that
+// keeps the unseen values in order.
+fn fill_labels(labels: [u8; 10]) -> [CellValue; 9] {
+    let mut unused = (1..10).filter(|v| !labels.contains(v));
+    let mut values = CellValue::ALL;
+    for (value, &label) in values.iter_mut().zip(&labels[1..]) {
+        let label = match label {
+            0 => unused.next().unwrap(),
+            label => label,
+        };
+        *value = CellValue::new(label).unwrap();
+    }
+    values
+}
+
+// The rows that can come at `depth`, given the rows already placed before it. The first row of a
+// band can come from any band not used yet, and the others from the same band as it.
+fn next_rows(depth: usize, rows: &[usize; 9]) -> Vec<usize> {
+    if depth % 3 == 0 {
+        (0..9)
+            .filter(|&row| rows[..depth].iter().all(|used| used / 3 != row / 3))
+            .collect()
+    } else {
+        let band = rows[depth - 1] / 3;
+        (3 * band..3 * band + 3)
+            .filter(|row| !rows[depth - depth % 3..depth].contains(row))
+            .collect()
+    }
+}
+
+// Every way of ordering the nine rows (or columns) that keeps the bands (or stacks) together, as
+// the old line for each new position.
+fn line_orders() -> Vec<[usize; 9]> {
+    const PERMS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMS {
+        for first in PERMS {
+            for second in PERMS {
+                for third in PERMS {
+                    let mut order = [0; 9];
+                    for (i, within) in [first, second, third].iter().enumerate() {
+                        for j in 0..3 {
+                            order[3 * i + j] = 3 * bands[i] + within[j];
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+fn assert_lines(order: [usize; 9]) {
+    let keeps_bands = (0..9).step_by(3).all(|i| {
+        let band = order[i] / 3;
+        let mut lines = [order[i], order[i + 1], order[i + 2]];
+        lines.sort_unstable();
+        lines == [3 * band, 3 * band + 1, 3 * band + 2]
+    });
+    let mut bands = [order[0] / 3, order[3] / 3, order[6] / 3];
+    bands.sort_unstable();
+    assert!(
+        keeps_bands && bands == [0, 1, 2],
+        "{:?} isn't an ordering of the lines that keeps the bands together",
+        order
+    );
+}
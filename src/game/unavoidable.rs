@@ -0,0 +1,175 @@
+// Unavoidable sets: groups of cells in a solution whose values could be rearranged among
+// themselves into another solution. A puzzle for that solution needs a clue in every one of them,
+// or it has more than one solution.
+
+use super::{Coord, Game};
+use std::cmp::Reverse;
+
+impl Game {
+    /// The minimal unavoidable sets of a full board with up to `max_size` cells each. Erasing all
+    /// the cells of an unavoidable set leaves a board with more than one way to fill it back in,
+    /// so every puzzle with this solution has a clue in each of them. Minimal means no smaller
+    /// unavoidable set is part of it. The sets come smallest first, ties in board order, each with
+    /// its cells in board order. A board that isn't full and valid has none.
+    ///
+    /// No unavoidable set has fewer than 4 cells. The search looks at every other solution that
+    /// differs from this one in at most `max_size` cells. Up to 12 takes well under a second in a
+    /// release build, and each size past that takes two or three times as long as the last.
+    pub fn unavoidable_sets(&self, max_size: usize) -> Vec<Vec<Coord>> {
+        if !self.solved() {
+            return Vec::new();
+        }
+        let mut search = Search {
+            solution: self
+                .board
+                .map(|row| row.map(|cell| cell.map_or(0, |cv| 1 << usize::from(cv)))),
+            rows: [0; 9],
+            cols: [0; 9],
+            sqrs: [0; 9],
+            max_size,
+            found: Vec::new(),
+        };
+        search.fill(0, 0, 0);
+        // Erasing the cells where another solution differs is enough to let it in, so the minimal
+        // unavoidable sets are the smallest of those differences. Anything smaller than a set found
+        // would have been found too, so checking against the others is enough.
+        let mut found = search.found;
+        found.sort_by_key(|&set| (set.count_ones(), Reverse(set.reverse_bits())));
+        found.dedup();
+        let mut minimal = Vec::<u128>::new();
+        for set in found {
+            if minimal.iter().all(|&smaller| smaller & set != smaller) {
+                minimal.push(set);
+            }
+        }
+        minimal
+            .into_iter()
+            .map(|set| {
+                (0..81)
+                    .filter(|&k| set & 1 << k != 0)
+                    .map(Game::coords)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// A search for the other solutions close to one solution, filling in the board a cell at a time in
+// board order. Values are bitmasks, with bit `i` for the value `i + 1`.
+struct Search {
+    solution: [[u16; 9]; 9],
+    // The values used so far in each row, column, and 3x3
+    rows: [u16; 9],
+    cols: [u16; 9],
+    sqrs: [u16; 9],
+    max_size: usize,
+    // The cells where each solution found differs, as bit `9 * row + col`
+    found: Vec<u128>,
+}
+
+impl Search {
+    // Fills in cells `k` onward, with the cells before it differing from the solution in `diff`,
+    // `changed` of them.
+    fn fill(&mut self, k: usize, diff: u128, changed: usize) {
+        if k == 81 {
+            if changed > 0 {
+                self.found.push(diff);
+            }
+            return;
+        }
+        // Every empty cell whose own value is already used in one of its units has to differ too.
+        if changed + self.blocked(k) > self.max_size {
+            return;
+        }
+        let (y, x) = Game::coords(k);
+        let s = 3 * (y / 3) + x / 3;
+        let free = 0x1ff & !(self.rows[y] | self.cols[x] | self.sqrs[s]);
+        for i in (0..9).filter(|&i| free & 1 << i != 0) {
+            let bit = 1 << i;
+            let differs = bit != self.solution[y][x];
+            if differs && changed == self.max_size {
+                continue;
+            }
+            self.rows[y] |= bit;
+            self.cols[x] |= bit;
+            self.sqrs[s] |= bit;
+            if differs {
+                self.fill(k + 1, diff | 1 << k, changed + 1);
+            } else {
+                self.fill(k + 1, diff, changed);
+            }
+            self.rows[y] &= !bit;
+            self.cols[x] &= !bit;
+            self.sqrs[s] &= !bit;
+        }
+    }
+
+    // How many of the cells from `k` onward can't take their value from the solution any more.
+    fn blocked(&self, k: usize) -> usize {
+        (k..81)
+            .map(Game::coords)
+            .filter(|&(y, x)| {
+                let used = self.rows[y] | self.cols[x] | self.sqrs[3 * (y / 3) + x / 3];
+                used & self.solution[y][x] != 0
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Coord, Game};
+    use crate::gameboards::EASY;
+
+    fn easy_solution() -> Game {
+        Game::new(Game::new(EASY).solved_array().unwrap())
+    }
+
+    fn erased(solution: &Game, cells: &[Coord]) -> Game {
+        let mut numbers = solution.to_array();
+        for &(y, x) in cells {
+            numbers[y][x] = 0;
+        }
+        Game::new(numbers)
+    }
+
+    #[test]
+    fn smallest_sets_are_rectangles() {
+        let solution = easy_solution();
+        let s = solution.to_array();
+        // Two rows and two columns meeting in two 3x3s, holding `a b` and `b a`
+        let mut rectangles = Vec::new();
+        for (r1, r2) in (0..9).flat_map(|r1| (r1 + 1..9).map(move |r2| (r1, r2))) {
+            for (c1, c2) in (0..9).flat_map(|c1| (c1 + 1..9).map(move |c2| (c1, c2))) {
+                let two_boxes = (r1 / 3 == r2 / 3) != (c1 / 3 == c2 / 3);
+                if two_boxes && s[r1][c1] == s[r2][c2] && s[r1][c2] == s[r2][c1] {
+                    rectangles.push(vec![(r1, c1), (r1, c2), (r2, c1), (r2, c2)]);
+                }
+            }
+        }
+        rectangles.sort();
+        assert!(!rectangles.is_empty());
+        assert_eq!(solution.unavoidable_sets(4), rectangles);
+        assert!(solution.unavoidable_sets(3).is_empty());
+        assert!(Game::new(EASY).unavoidable_sets(8).is_empty());
+    }
+
+    #[test]
+    fn sets_are_unavoidable_and_minimal() {
+        let solution = easy_solution();
+        let sets = solution.unavoidable_sets(8);
+        assert!(sets.iter().any(|set| set.len() == 8));
+        assert!(sets.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+        for set in &sets {
+            assert!(set.windows(2).all(|pair| pair[0] < pair[1]));
+            // Erasing the whole set lets another solution in, and keeping any one of its cells
+            // shuts it out again.
+            assert_eq!(erased(&solution, set).count_solutions(2), 2, "{:?}", set);
+            for i in 0..set.len() {
+                let mut fewer = set.clone();
+                fewer.remove(i);
+                assert_eq!(erased(&solution, &fewer).count_solutions(2), 1, "{:?}", set);
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{Game, Puzzle, Solution};
+
 pub const EASY: [[u8; 9]; 9] = [
     [2, 0, 5, 0, 0, 9, 0, 0, 4],
     [0, 0, 0, 0, 0, 0, 3, 0, 7],
@@ -42,4 +44,299 @@ pub const SEVENTEEN: [[u8; 9]; 9] = [
     [0, 0, 0, 0, 4, 0, 0, 9, 0],
     [0, 2, 0, 0, 0, 0, 6, 0, 0],
 ];
+// An empty board, which deliberately has every possible solution rather than a unique one
 pub const ZEROS: [[u8; 9]; 9] = [[0; 9]; 9];
+// Arto Inkala's "AI Escargot"
+pub const AI_ESCARGOT: [[u8; 9]; 9] = [
+    [1, 0, 0, 0, 0, 7, 0, 9, 0],
+    [0, 3, 0, 0, 2, 0, 0, 0, 8],
+    [0, 0, 9, 6, 0, 0, 5, 0, 0],
+    [0, 0, 5, 3, 0, 0, 9, 0, 0],
+    [0, 1, 0, 0, 8, 0, 0, 0, 2],
+    [6, 0, 0, 0, 0, 4, 0, 0, 0],
+    [3, 0, 0, 0, 0, 0, 0, 1, 0],
+    [0, 4, 0, 0, 0, 0, 0, 0, 7],
+    [0, 0, 7, 0, 0, 0, 3, 0, 0],
+];
+pub const AI_ESCARGOT_SOLUTION: [[u8; 9]; 9] = [
+    [1, 6, 2, 8, 5, 7, 4, 9, 3],
+    [5, 3, 4, 1, 2, 9, 6, 7, 8],
+    [7, 8, 9, 6, 4, 3, 5, 2, 1],
+    [4, 7, 5, 3, 1, 2, 9, 8, 6],
+    [9, 1, 3, 5, 8, 6, 7, 4, 2],
+    [6, 2, 8, 7, 9, 4, 1, 3, 5],
+    [3, 5, 6, 4, 7, 8, 2, 1, 9],
+    [2, 4, 1, 9, 3, 5, 8, 6, 7],
+    [8, 9, 7, 2, 6, 1, 3, 5, 4],
+];
+// "Easter Monster"
+pub const EASTER_MONSTER: [[u8; 9]; 9] = [
+    [1, 0, 0, 0, 0, 0, 0, 0, 2],
+    [0, 9, 0, 4, 0, 0, 0, 5, 0],
+    [0, 0, 6, 0, 0, 0, 7, 0, 0],
+    [0, 5, 0, 9, 0, 3, 0, 0, 0],
+    [0, 0, 0, 0, 7, 0, 0, 0, 0],
+    [0, 0, 0, 8, 5, 0, 0, 4, 0],
+    [7, 0, 0, 0, 0, 0, 6, 0, 0],
+    [0, 3, 0, 0, 0, 9, 0, 8, 0],
+    [0, 0, 2, 0, 0, 0, 0, 0, 1],
+];
+pub const EASTER_MONSTER_SOLUTION: [[u8; 9]; 9] = [
+    [1, 7, 4, 3, 8, 5, 9, 6, 2],
+    [2, 9, 3, 4, 6, 7, 1, 5, 8],
+    [5, 8, 6, 1, 9, 2, 7, 3, 4],
+    [4, 5, 1, 9, 2, 3, 8, 7, 6],
+    [9, 2, 8, 6, 7, 4, 3, 1, 5],
+    [3, 6, 7, 8, 5, 1, 2, 4, 9],
+    [7, 1, 9, 5, 4, 8, 6, 2, 3],
+    [6, 3, 5, 2, 1, 9, 4, 8, 7],
+    [8, 4, 2, 7, 3, 6, 5, 9, 1],
+];
+// "Golden Nugget"
+pub const GOLDEN_NUGGET: [[u8; 9]; 9] = [
+    [0, 0, 0, 0, 0, 0, 0, 3, 9],
+    [0, 0, 0, 0, 0, 1, 0, 0, 5],
+    [0, 0, 3, 0, 5, 0, 8, 0, 0],
+    [0, 0, 8, 0, 9, 0, 0, 0, 6],
+    [0, 7, 0, 0, 0, 2, 0, 0, 0],
+    [1, 0, 0, 4, 0, 0, 0, 0, 0],
+    [0, 0, 9, 0, 8, 0, 0, 5, 0],
+    [0, 2, 0, 0, 0, 0, 6, 0, 0],
+    [4, 0, 0, 7, 0, 0, 0, 0, 0],
+];
+pub const GOLDEN_NUGGET_SOLUTION: [[u8; 9]; 9] = [
+    [7, 5, 1, 8, 4, 6, 2, 3, 9],
+    [8, 9, 2, 3, 7, 1, 4, 6, 5],
+    [6, 4, 3, 2, 5, 9, 8, 7, 1],
+    [2, 3, 8, 1, 9, 7, 5, 4, 6],
+    [9, 7, 4, 5, 6, 2, 3, 1, 8],
+    [1, 6, 5, 4, 3, 8, 9, 2, 7],
+    [3, 1, 9, 6, 8, 4, 7, 5, 2],
+    [5, 2, 7, 9, 1, 3, 6, 8, 4],
+    [4, 8, 6, 7, 2, 5, 1, 9, 3],
+];
+// "Platinum Blonde"
+pub const PLATINUM_BLONDE: [[u8; 9]; 9] = [
+    [0, 0, 0, 0, 0, 0, 0, 1, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 3],
+    [0, 0, 2, 3, 0, 0, 4, 0, 0],
+    [0, 0, 1, 8, 0, 0, 0, 0, 5],
+    [0, 6, 0, 0, 7, 0, 8, 0, 0],
+    [0, 0, 0, 0, 0, 9, 0, 0, 0],
+    [0, 0, 8, 5, 0, 0, 0, 0, 0],
+    [9, 0, 0, 0, 4, 0, 5, 0, 0],
+    [4, 7, 0, 0, 0, 6, 0, 0, 0],
+];
+pub const PLATINUM_BLONDE_SOLUTION: [[u8; 9]; 9] = [
+    [8, 3, 9, 4, 6, 5, 7, 1, 2],
+    [1, 4, 6, 7, 8, 2, 9, 5, 3],
+    [7, 5, 2, 3, 9, 1, 4, 8, 6],
+    [3, 9, 1, 8, 2, 4, 6, 7, 5],
+    [5, 6, 4, 1, 7, 3, 8, 2, 9],
+    [2, 8, 7, 6, 5, 9, 3, 4, 1],
+    [6, 2, 8, 5, 3, 7, 1, 9, 4],
+    [9, 1, 3, 2, 4, 8, 5, 6, 7],
+    [4, 7, 5, 9, 1, 6, 2, 3, 8],
+];
+// The first puzzle of the "top95" collection, which has 17 clues
+pub const TOP95_1: [[u8; 9]; 9] = [
+    [4, 0, 0, 0, 0, 0, 8, 0, 5],
+    [0, 3, 0, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 7, 0, 0, 0, 0, 0],
+    [0, 2, 0, 0, 0, 0, 0, 6, 0],
+    [0, 0, 0, 0, 8, 0, 4, 0, 0],
+    [0, 0, 0, 0, 1, 0, 0, 0, 0],
+    [0, 0, 0, 6, 0, 3, 0, 7, 0],
+    [5, 0, 0, 2, 0, 0, 0, 0, 0],
+    [1, 0, 4, 0, 0, 0, 0, 0, 0],
+];
+pub const TOP95_1_SOLUTION: [[u8; 9]; 9] = [
+    [4, 1, 7, 3, 6, 9, 8, 2, 5],
+    [6, 3, 2, 1, 5, 8, 9, 4, 7],
+    [9, 5, 8, 7, 2, 4, 3, 1, 6],
+    [8, 2, 5, 4, 3, 7, 1, 6, 9],
+    [7, 9, 1, 5, 8, 6, 4, 3, 2],
+    [3, 4, 6, 9, 1, 2, 7, 5, 8],
+    [2, 8, 9, 6, 4, 3, 5, 7, 1],
+    [5, 7, 3, 2, 9, 1, 6, 8, 4],
+    [1, 6, 4, 8, 7, 5, 2, 9, 3],
+];
+// The second puzzle of the "top95" collection, which has 17 clues
+pub const TOP95_2: [[u8; 9]; 9] = [
+    [5, 2, 0, 0, 0, 6, 0, 0, 0],
+    [0, 0, 0, 0, 0, 0, 7, 0, 1],
+    [3, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 4, 0, 0, 8, 0, 0],
+    [6, 0, 0, 0, 0, 0, 0, 5, 0],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 4, 1, 8, 0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 3, 0, 0, 2, 0],
+    [0, 0, 8, 7, 0, 0, 0, 0, 0],
+];
+pub const TOP95_2_SOLUTION: [[u8; 9]; 9] = [
+    [5, 2, 7, 3, 1, 6, 4, 8, 9],
+    [8, 9, 6, 5, 4, 2, 7, 3, 1],
+    [3, 1, 4, 9, 8, 7, 5, 6, 2],
+    [1, 7, 2, 4, 5, 3, 8, 9, 6],
+    [6, 8, 9, 2, 7, 1, 3, 5, 4],
+    [4, 5, 3, 6, 9, 8, 2, 1, 7],
+    [9, 4, 1, 8, 2, 5, 6, 7, 3],
+    [7, 6, 5, 1, 3, 4, 9, 2, 8],
+    [2, 3, 8, 7, 6, 9, 1, 4, 5],
+];
+// Needs an X-chain: the fish, turbot fish, and other techniques of the same tier can't finish it
+pub const X_CHAIN: [[u8; 9]; 9] = [
+    [6, 3, 0, 2, 0, 0, 9, 0, 4],
+    [0, 0, 7, 4, 0, 1, 0, 6, 0],
+    [1, 0, 0, 0, 0, 0, 0, 7, 0],
+    [0, 0, 6, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 4, 0, 0, 3, 6, 2, 0, 0],
+    [0, 0, 0, 0, 7, 2, 1, 4, 0],
+    [0, 8, 0, 9, 0, 4, 0, 0, 3],
+    [0, 0, 0, 6, 0, 0, 0, 0, 0],
+];
+pub const X_CHAIN_SOLUTION: [[u8; 9]; 9] = [
+    [6, 3, 5, 2, 8, 7, 9, 1, 4],
+    [8, 2, 7, 4, 9, 1, 3, 6, 5],
+    [1, 9, 4, 3, 6, 5, 8, 7, 2],
+    [3, 5, 6, 7, 2, 8, 4, 9, 1],
+    [2, 7, 8, 1, 4, 9, 5, 3, 6],
+    [9, 4, 1, 5, 3, 6, 2, 8, 7],
+    [5, 6, 3, 8, 7, 2, 1, 4, 9],
+    [7, 8, 2, 9, 1, 4, 6, 5, 3],
+    [4, 1, 9, 6, 5, 3, 7, 2, 8],
+];
+
+/// A board from this module along with its known solution, if it has one stored.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct NamedBoard {
+    pub name: &'static str,
+    pub board: [[u8; 9]; 9],
+    pub solution: Option<[[u8; 9]; 9]>,
+}
+
+impl NamedBoard {
+    /// The board as a checked `Puzzle`. Every board here is valid, so this never fails.
+    pub fn puzzle(&self) -> Puzzle {
+        Puzzle::new(Game::new(self.board)).unwrap()
+    }
+
+    /// The stored solution as a checked `Solution`, if there is one.
+    pub fn known_solution(&self) -> Option<Solution> {
+        self.solution
+            .map(|solution| Solution::new(Game::new(solution)).unwrap())
+    }
+}
+
+const NAMED_BOARDS: [NamedBoard; 12] = [
+    NamedBoard {
+        name: "EASY",
+        board: EASY,
+        solution: None,
+    },
+    NamedBoard {
+        name: "MEDIUM",
+        board: MEDIUM,
+        solution: None,
+    },
+    NamedBoard {
+        name: "HARD",
+        board: HARD,
+        solution: None,
+    },
+    NamedBoard {
+        name: "SEVENTEEN",
+        board: SEVENTEEN,
+        solution: None,
+    },
+    NamedBoard {
+        name: "ZEROS",
+        board: ZEROS,
+        solution: None,
+    },
+    NamedBoard {
+        name: "AI_ESCARGOT",
+        board: AI_ESCARGOT,
+        solution: Some(AI_ESCARGOT_SOLUTION),
+    },
+    NamedBoard {
+        name: "EASTER_MONSTER",
+        board: EASTER_MONSTER,
+        solution: Some(EASTER_MONSTER_SOLUTION),
+    },
+    NamedBoard {
+        name: "GOLDEN_NUGGET",
+        board: GOLDEN_NUGGET,
+        solution: Some(GOLDEN_NUGGET_SOLUTION),
+    },
+    NamedBoard {
+        name: "PLATINUM_BLONDE",
+        board: PLATINUM_BLONDE,
+        solution: Some(PLATINUM_BLONDE_SOLUTION),
+    },
+    NamedBoard {
+        name: "TOP95_1",
+        board: TOP95_1,
+        solution: Some(TOP95_1_SOLUTION),
+    },
+    NamedBoard {
+        name: "TOP95_2",
+        board: TOP95_2,
+        solution: Some(TOP95_2_SOLUTION),
+    },
+    NamedBoard {
+        name: "X_CHAIN",
+        board: X_CHAIN,
+        solution: Some(X_CHAIN_SOLUTION),
+    },
+];
+
+/// Every board in this module, paired with its known solution where one is stored, for checking
+/// solvers against.
+pub fn with_solutions() -> &'static [NamedBoard] {
+    &NAMED_BOARDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_solutions_match_the_solver() {
+        for named in with_solutions() {
+            let solution = match named.solution {
+                Some(solution) => solution,
+                None => continue,
+            };
+            // The stored solution has to stand on its own: full, within the rules, and keeping
+            // every given.
+            assert!(named.known_solution().is_some(), "{}", named.name);
+            for (given, value) in named.board.iter().flatten().zip(solution.iter().flatten()) {
+                assert!(*given == 0 || given == value, "{}", named.name);
+            }
+            let mut game = Game::new(named.board);
+            game.solve().unwrap();
+            assert_eq!(game.to_array(), solution, "{}", named.name);
+            assert_eq!(
+                Game::new(named.board).count_solutions(2),
+                1,
+                "{}",
+                named.name
+            );
+        }
+    }
+
+    #[test]
+    fn built_in_boards_are_well_formed() {
+        for named in with_solutions() {
+            if named.name == "ZEROS" {
+                // An empty board, on purpose, so it has every solution there is.
+                assert!(!Game::new(named.board).is_well_formed());
+                assert_eq!(Game::new(named.board).count_solutions(2), 2);
+            } else {
+                assert!(Game::new(named.board).is_well_formed(), "{}", named.name);
+            }
+        }
+    }
+}
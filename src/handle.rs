@@ -0,0 +1,202 @@
+// Solving on a background thread, for programs like GUIs that can't block while a hard game is
+// searched.
+
+use crate::game::{Game, SearchHooks, SolveError};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// How far a background solve has gotten.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Progress {
+    /// Search tree nodes visited so far
+    pub nodes: usize,
+    /// Whether the solve has finished, successfully or not
+    pub finished: bool,
+}
+
+struct Shared {
+    cancel: AtomicBool,
+    nodes: AtomicUsize,
+    result: Mutex<Option<Result<Game, SolveError>>>,
+    #[cfg(feature = "async")]
+    waker: Mutex<Option<Waker>>,
+}
+
+/// A solve running on a background thread, started with `spawn_solve`. Dropping the handle cancels
+/// the solve.
+pub struct SolveHandle {
+    shared: Arc<Shared>,
+}
+
+/// Starts solving a game on a new thread and returns a handle to check on it. With the `async`
+/// feature enabled, the handle can also be awaited. If the solver panics, the solve finishes with
+/// `SolveError::Panicked`.
+pub fn spawn_solve(game: Game) -> SolveHandle {
+    spawn_with(move |hooks| {
+        let mut game = game;
+        game.solve_hooked(hooks).map(|()| game)
+    })
+}
+
+// Runs `solve` on a new thread with hooks tied to the handle. A panic is caught and stored as the
+// result, since otherwise the handle would never finish.
+fn spawn_with(
+    solve: impl FnOnce(&SearchHooks) -> Result<Game, SolveError> + Send + 'static,
+) -> SolveHandle {
+    let shared = Arc::new(Shared {
+        cancel: AtomicBool::new(false),
+        nodes: AtomicUsize::new(0),
+        result: Mutex::new(None),
+        #[cfg(feature = "async")]
+        waker: Mutex::new(None),
+    });
+    let worker = Arc::clone(&shared);
+    thread::spawn(move || {
+        let hooks = SearchHooks {
+            cancel: Some(&worker.cancel),
+            nodes: Some(&worker.nodes),
+            ..SearchHooks::default()
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solve(&hooks)))
+            .unwrap_or(Err(SolveError::Panicked));
+        *worker.result.lock().unwrap() = Some(result);
+        #[cfg(feature = "async")]
+        {
+            if let Some(waker) = worker.waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        }
+    });
+    SolveHandle { shared }
+}
+
+impl SolveHandle {
+    /// Returns the result if the solve has finished, without waiting for it.
+    pub fn poll(&self) -> Option<Result<Game, SolveError>> {
+        *self.shared.result.lock().unwrap()
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            nodes: self.shared.nodes.load(Ordering::Relaxed),
+            finished: self.shared.result.lock().unwrap().is_some(),
+        }
+    }
+
+    /// Asks the solve to stop. It will finish with `SolveError::Cancelled` the next time it visits
+    /// a search node, unless it has already finished.
+    pub fn cancel(&self) {
+        self.shared.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SolveHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(feature = "async")]
+impl Future for SolveHandle {
+    type Output = Result<Game, SolveError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // Register the waker before checking for the result, so that a result stored in between
+        // can't be missed.
+        *self.shared.waker.lock().unwrap() = Some(cx.waker().clone());
+        match *self.shared.result.lock().unwrap() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboards::{EASY, SEVENTEEN};
+    use std::time::{Duration, Instant};
+
+    // Polls until the solve finishes, failing the test if that takes longer than `limit`.
+    fn wait(handle: &SolveHandle, limit: Duration) -> Result<Game, SolveError> {
+        let start = Instant::now();
+        loop {
+            if let Some(result) = handle.poll() {
+                return result;
+            }
+            assert!(
+                start.elapsed() < limit,
+                "solve didn't finish in {:?}",
+                limit
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn easy_solution() -> [[u8; 9]; 9] {
+        Game::new(EASY).solved_array().unwrap()
+    }
+
+    #[test]
+    fn cancelling_stops_promptly() {
+        let handle = spawn_solve(Game::new(SEVENTEEN));
+        handle.cancel();
+        assert_eq!(
+            wait(&handle, Duration::from_secs(1)).err(),
+            Some(SolveError::Cancelled)
+        );
+        assert!(handle.progress().finished);
+    }
+
+    #[test]
+    fn solves_in_the_background() {
+        let handle = spawn_solve(Game::new(EASY));
+        let game = wait(&handle, Duration::from_secs(10)).unwrap();
+        assert_eq!(game.to_array(), easy_solution());
+        assert!(handle.progress().nodes > 0);
+    }
+
+    #[test]
+    fn a_panic_finishes_the_solve() {
+        let handle = spawn_with(|_| panic!("solver bug"));
+        assert_eq!(
+            wait(&handle, Duration::from_secs(10)).err(),
+            Some(SolveError::Panicked)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn awaits_the_solution() {
+        use std::sync::Arc;
+        use std::task::Wake;
+
+        // Just enough of an executor to run one future on this thread.
+        struct Unpark(thread::Thread);
+
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let mut handle = spawn_solve(Game::new(EASY));
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let result = loop {
+            match Pin::new(&mut handle).poll(&mut cx) {
+                Poll::Ready(result) => break result,
+                Poll::Pending => thread::park_timeout(Duration::from_secs(1)),
+            }
+        };
+        assert_eq!(result.unwrap().to_array(), easy_solution());
+    }
+}
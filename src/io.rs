@@ -0,0 +1,230 @@
+// Reading and writing collections of games.
+
+use crate::{Difficulty, Game};
+use std::io::{self, BufRead, Write};
+
+pub mod collection;
+pub mod dedup;
+#[cfg(feature = "serde")]
+pub mod exchange;
+mod paste;
+pub mod records;
+pub mod replay;
+pub mod stats;
+
+pub use paste::{normalize, normalize_with, NormalizeError, DEFAULT_BLANKS};
+
+/// Settings for `stream_solve`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BatchOptions {
+    /// Flush the output after this many solved games
+    pub flush_every: usize,
+    /// With the `parallel` feature, the most games read ahead and solved at once. Without it,
+    /// games are solved one at a time regardless.
+    pub window: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            flush_every: 1000,
+            window: 64,
+        }
+    }
+}
+
+/// Counts of what happened to each line in a batch.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct BatchSummary {
+    /// Non-blank lines read
+    pub read: usize,
+    pub solved: usize,
+    /// Lines that couldn't be read as a game
+    pub malformed: usize,
+    /// Games with no solution
+    pub unsolvable: usize,
+}
+
+/// Solves one game per line from `reader`, writing each solution as a line to `writer` in the same
+/// order. Blank lines are skipped, and lines that aren't games or have no solution are counted
+/// and left out of the output. The input is never held in memory all at once, so this works on
+/// files of any size.
+pub fn stream_solve(
+    reader: impl BufRead,
+    writer: impl Write,
+    opts: &BatchOptions,
+) -> io::Result<BatchSummary> {
+    stream_solve_with_errors(reader, writer, io::sink(), opts)
+}
+
+/// Like `stream_solve`, but also echoes every line that didn't produce a solution to `errors`.
+pub fn stream_solve_with_errors(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    mut errors: impl Write,
+    opts: &BatchOptions,
+) -> io::Result<BatchSummary> {
+    let mut summary = BatchSummary::default();
+    let window = if cfg!(feature = "parallel") {
+        opts.window.max(1)
+    } else {
+        1
+    };
+    let mut lines = reader.lines();
+    let mut chunk = Vec::with_capacity(window);
+    loop {
+        chunk.clear();
+        for line in lines.by_ref() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                chunk.push(line);
+                if chunk.len() == window {
+                    break;
+                }
+            }
+        }
+        if chunk.is_empty() {
+            break;
+        }
+        for (line, result) in chunk.iter().zip(map_batch(&chunk, |line| solve_line(line))) {
+            summary.read += 1;
+            match result {
+                Some(Some(game)) => {
+                    writeln!(writer, "{}", game.to_line())?;
+                    summary.solved += 1;
+                    if opts.flush_every > 0 && summary.solved % opts.flush_every == 0 {
+                        writer.flush()?;
+                    }
+                }
+                Some(None) => {
+                    writeln!(errors, "{}", line)?;
+                    summary.unsolvable += 1;
+                }
+                None => {
+                    writeln!(errors, "{}", line)?;
+                    summary.malformed += 1;
+                }
+            }
+        }
+    }
+    writer.flush()?;
+    errors.flush()?;
+    Ok(summary)
+}
+
+// Parses and solves each line, giving `None` for a line that isn't a game and `Some(None)` for a
+// game with no solution.
+fn solve_line(line: &str) -> Option<Option<Game>> {
+    line.parse::<Game>()
+        .ok()
+        .map(|mut game| game.solve().ok().map(|()| game))
+}
+
+/// Rates every puzzle, giving the same `Difficulty` as `Game::difficulty` would for each, in the
+/// same order. Only the ratings are kept, not the solutions. With the `parallel` feature, the
+/// puzzles are spread across threads.
+pub fn rate_many(puzzles: &[Game]) -> Vec<Difficulty> {
+    map_batch(puzzles, Game::difficulty)
+}
+
+// Applies `f` to every item, keeping the results in order.
+#[cfg(not(feature = "parallel"))]
+fn map_batch<T, R>(items: &[T], f: impl Fn(&T) -> R) -> Vec<R> {
+    items.iter().map(f).collect()
+}
+
+// Applies `f` to every item, splitting the items evenly across the available threads and keeping
+// the results in order.
+#[cfg(feature = "parallel")]
+fn map_batch<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let per_thread = items.len().div_ceil(threads).max(1);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let workers = items
+            .chunks(per_thread)
+            .map(|items| scope.spawn(move || items.iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stream_solve, stream_solve_with_errors, BatchOptions, BatchSummary};
+    use crate::gameboards::{EASY, MEDIUM};
+    use crate::Game;
+    use std::io::Cursor;
+
+    // R1C8 and R1C9 have to hold 8 and 9 between them, but both their columns already have a 9.
+    const NO_SOLUTION: &str =
+        "1234567...........................9...........................9..................";
+
+    // 10,000 lines cycling through two puzzles, a blank line, a line that isn't a game, and a game
+    // with no solution.
+    fn batch() -> String {
+        let lines = [
+            Game::new(EASY).to_line(),
+            Game::new(MEDIUM).to_line(),
+            String::new(),
+            "not a sudoku".to_string(),
+            NO_SOLUTION.to_string(),
+        ];
+        let mut input = String::new();
+        for line in lines.iter().cycle().take(10_000) {
+            input.push_str(line);
+            input.push('\n');
+        }
+        input
+    }
+
+    #[test]
+    fn streams_a_large_batch_in_order() {
+        let solutions = [
+            Game::new(EASY)
+                .solved_array()
+                .map(|a| Game::new(a).to_line()),
+            Game::new(MEDIUM)
+                .solved_array()
+                .map(|a| Game::new(a).to_line()),
+        ];
+        let expected = solutions.iter().flatten().cycle().take(4000).cloned();
+        let expected = expected.map(|line| line + "\n").collect::<String>();
+        let summary = BatchSummary {
+            read: 8000,
+            solved: 4000,
+            malformed: 2000,
+            unsolvable: 2000,
+        };
+        let input = batch();
+        for opts in &[
+            BatchOptions::default(),
+            BatchOptions {
+                flush_every: 3,
+                window: 7,
+            },
+        ] {
+            let mut out = Vec::new();
+            let got = stream_solve(Cursor::new(input.as_bytes()), &mut out, opts).unwrap();
+            assert_eq!(got, summary);
+            assert_eq!(String::from_utf8(out).unwrap(), expected);
+        }
+
+        let (mut out, mut errors) = (Vec::new(), Vec::new());
+        stream_solve_with_errors(
+            Cursor::new(input.as_bytes()),
+            &mut out,
+            &mut errors,
+            &BatchOptions::default(),
+        )
+        .unwrap();
+        let errors = String::from_utf8(errors).unwrap();
+        assert_eq!(errors.lines().count(), 4000);
+        assert!(errors
+            .lines()
+            .all(|line| line == "not a sudoku" || line == NO_SOLUTION));
+    }
+}
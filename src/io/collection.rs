@@ -0,0 +1,76 @@
+// Drawing samples from collections of puzzles, for building test sets that look like the whole
+// collection: each group, like a difficulty tier, gets its share of the sample.
+
+use crate::{SplitMix64, SudokuRng};
+use std::collections::BTreeMap;
+
+/// Splits items in two, with `n` of them in the first part, chosen at random from each group of
+/// items with the same key in proportion to the group's size. Each group's share is rounded down,
+/// and what's left over goes to the groups that lost the most to rounding, ties going to the
+/// group with the smallest key. Both parts keep the items in their original order, and no item is
+/// in both. With `n` at least the number of items, everything goes in the first part. The same
+/// seed always makes the same split.
+pub fn stratified_split<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+    n: usize,
+    seed: u64,
+) -> (Vec<T>, Vec<T>) {
+    let items = items.into_iter().collect::<Vec<_>>();
+    let total = items.len();
+    let n = n.min(total);
+    let mut groups = BTreeMap::<K, Vec<usize>>::new();
+    for (i, item) in items.iter().enumerate() {
+        groups.entry(key(item)).or_default().push(i);
+    }
+    // Each group's share, rounded down, and how much rounding took off it, in units of
+    // `1 / total`
+    let mut shares = groups
+        .values()
+        .map(|members| {
+            let exact = members.len() * n;
+            (exact / total.max(1), exact % total.max(1))
+        })
+        .collect::<Vec<_>>();
+    let mut short = n - shares.iter().map(|&(share, _)| share).sum::<usize>();
+    let mut order = (0..shares.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&g| std::cmp::Reverse(shares[g].1));
+    for &g in &order {
+        if short == 0 {
+            break;
+        }
+        shares[g].0 += 1;
+        short -= 1;
+    }
+    let mut rng = SplitMix64::new(seed);
+    let mut chosen = vec![false; total];
+    for (members, &(share, _)) in groups.values_mut().zip(&shares) {
+        // The first `share` places of a shuffle, without shuffling the rest
+        for i in 0..share {
+            let j = i + rng.below(members.len() - i);
+            members.swap(i, j);
+            chosen[members[i]] = true;
+        }
+    }
+    let mut first = Vec::with_capacity(n);
+    let mut second = Vec::with_capacity(total - n);
+    for (item, chosen) in items.into_iter().zip(chosen) {
+        if chosen {
+            first.push(item);
+        } else {
+            second.push(item);
+        }
+    }
+    (first, second)
+}
+
+/// The first part of `stratified_split`: `n` items, each group of items with the same key getting
+/// its share, in their original order.
+pub fn stratified_sample<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    key: impl FnMut(&T) -> K,
+    n: usize,
+    seed: u64,
+) -> Vec<T> {
+    stratified_split(items, key, n, seed).0
+}
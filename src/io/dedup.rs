@@ -0,0 +1,53 @@
+// Finding puzzles in a collection that are copies of each other, up to rearranging and relabeling.
+
+use crate::game::hash_form;
+use crate::Game;
+use std::collections::HashMap;
+
+/// A puzzle `dedup` dropped, and the earlier one it's a rearrangement of. Both are positions in
+/// the input, counting from 0.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Duplicate {
+    pub index: usize,
+    pub representative: usize,
+}
+
+/// What `dedup` kept and what it dropped.
+#[derive(Clone)]
+pub struct DedupResult {
+    /// The first puzzle of each group, with its position in the input, in input order
+    pub kept: Vec<(usize, Game)>,
+    /// Every other puzzle, in input order
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// Groups puzzles by `Game::canonical_form`, keeping the first of each group. Only a 64-bit
+/// fingerprint is held for each group, and puzzles with matching fingerprints have their
+/// canonical forms compared in full, so a hash collision never merges two different puzzles.
+pub fn dedup(games: impl Iterator<Item = Game>) -> DedupResult {
+    let mut result = DedupResult {
+        kept: Vec::new(),
+        duplicates: Vec::new(),
+    };
+    // Positions in `kept` for each fingerprint
+    let mut groups = HashMap::<u64, Vec<usize>>::new();
+    for (index, game) in games.enumerate() {
+        let form = game.canonical_form();
+        let group = groups.entry(hash_form(&form)).or_default();
+        let representative = group
+            .iter()
+            .map(|&k| result.kept[k])
+            .find(|(_, kept)| kept.canonical_form() == form);
+        match representative {
+            Some((representative, _)) => result.duplicates.push(Duplicate {
+                index,
+                representative,
+            }),
+            None => {
+                group.push(result.kept.len());
+                result.kept.push((index, game));
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,302 @@
+// A versioned JSON document for exchanging puzzles with other programs. It has room for variant
+// puzzles, like other grid sizes, jigsaw regions, and killer cages, so that documents from tools
+// that know about them still read, but this crate only plays classic sudoku and turns anything
+// else down. A classic puzzle looks like this:
+//
+//     {"version": 1, "size": 9, "box": [3, 3], "regions": null,
+//      "givens": "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+//      "rules": ["classic"], "cages": [], "thermos": [], "marks": [], "solution": null,
+//      "metadata": {"author": "..."}}
+//
+// Fields a document has that this version doesn't know about are ignored, so newer writers can
+// add to it without breaking older readers. A different version number is turned down.
+//
+// The variant fields are reserved: `regions` for jigsaw puzzles, `cages` for killer puzzles,
+// `thermos`, `marks`, and any rule besides `classic`. Their shapes are left to the version that
+// first supports them. This version always writes them empty, and reading a document that uses
+// any of them fails with `ExchangeError::Unsupported` naming the field, instead of dropping the
+// constraints and reading back a different puzzle.
+
+use super::records::{Metadata, PuzzleRecord};
+use crate::game::ParseError;
+use crate::Game;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+/// The version of the exchange document this crate reads and writes.
+pub const EXCHANGE_VERSION: u64 = 1;
+
+/// Reasons an exchange document can't be read as a game.
+#[derive(Debug)]
+pub enum ExchangeError {
+    /// The text isn't JSON, or doesn't have the document's fields
+    Json(serde_json::Error),
+    /// The document is a version this crate doesn't read, or has no version at all
+    Version(Option<u64>),
+    /// The document uses something classic sudoku doesn't have, named by its field
+    Unsupported(&'static str),
+    /// The givens aren't a game
+    Givens(ParseError),
+    /// The solution isn't a filled board that agrees with the givens
+    Solution,
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExchangeError::Json(e) => e.fmt(f),
+            ExchangeError::Version(Some(version)) => {
+                write!(f, "unsupported exchange version {}", version)
+            }
+            ExchangeError::Version(None) => write!(f, "missing exchange version"),
+            ExchangeError::Unsupported(field) => write!(f, "unsupported {}", field),
+            ExchangeError::Givens(e) => write!(f, "givens: {}", e),
+            ExchangeError::Solution => write!(f, "solution doesn't match the givens"),
+        }
+    }
+}
+
+impl Error for ExchangeError {}
+
+impl From<serde_json::Error> for ExchangeError {
+    fn from(other: serde_json::Error) -> Self {
+        ExchangeError::Json(other)
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct ExchangeMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    difficulty: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Document {
+    version: u64,
+    size: usize,
+    #[serde(rename = "box")]
+    box_shape: [usize; 2],
+    /// Reserved for jigsaw puzzles: the region of each cell, as rows of region numbers
+    #[serde(default)]
+    regions: Option<Vec<Vec<usize>>>,
+    givens: String,
+    /// The rule sets in play. Only `classic` is read; the rest are reserved for variants.
+    #[serde(default)]
+    rules: Vec<String>,
+    /// Reserved for killer puzzles
+    #[serde(default)]
+    cages: Vec<Value>,
+    /// Reserved for thermometer puzzles
+    #[serde(default)]
+    thermos: Vec<Value>,
+    /// Reserved for other marks on the grid, like kropki dots
+    #[serde(default)]
+    marks: Vec<Value>,
+    #[serde(default)]
+    solution: Option<String>,
+    #[serde(default)]
+    metadata: ExchangeMetadata,
+}
+
+impl Document {
+    fn classic(game: &Game, solution: Option<String>, meta: &Metadata) -> Self {
+        Document {
+            version: EXCHANGE_VERSION,
+            size: 9,
+            box_shape: [3, 3],
+            regions: None,
+            givens: game.to_line(),
+            rules: vec!["classic".to_string()],
+            cages: Vec::new(),
+            thermos: Vec::new(),
+            marks: Vec::new(),
+            solution,
+            metadata: ExchangeMetadata {
+                author: meta.author.clone(),
+                title: meta.title.clone(),
+                source: meta.source.clone(),
+                date: meta.date.clone(),
+                difficulty: meta.difficulty.clone(),
+            },
+        }
+    }
+
+    fn read(json: &str) -> Result<Self, ExchangeError> {
+        // The version decides how the rest is read, so it's checked before anything else.
+        let value = serde_json::from_str::<Value>(json)?;
+        match value.get("version").and_then(Value::as_u64) {
+            Some(EXCHANGE_VERSION) => {}
+            version => return Err(ExchangeError::Version(version)),
+        }
+        let doc = serde_json::from_value::<Document>(value)?;
+        if doc.size != 9 {
+            return Err(ExchangeError::Unsupported("size"));
+        }
+        if doc.box_shape != [3, 3] {
+            return Err(ExchangeError::Unsupported("box"));
+        }
+        if doc.regions.is_some() {
+            return Err(ExchangeError::Unsupported("regions"));
+        }
+        if doc.rules.iter().any(|rule| rule != "classic") {
+            return Err(ExchangeError::Unsupported("rules"));
+        }
+        if !doc.cages.is_empty() {
+            return Err(ExchangeError::Unsupported("cages"));
+        }
+        if !doc.thermos.is_empty() {
+            return Err(ExchangeError::Unsupported("thermos"));
+        }
+        if !doc.marks.is_empty() {
+            return Err(ExchangeError::Unsupported("marks"));
+        }
+        Ok(doc)
+    }
+
+    fn game(&self) -> Result<Game, ExchangeError> {
+        let game = self.givens.parse::<Game>().map_err(ExchangeError::Givens)?;
+        if let Some(solution) = &self.solution {
+            let solution = solution
+                .parse::<Game>()
+                .map_err(|_| ExchangeError::Solution)?;
+            let agrees = game
+                .to_line()
+                .bytes()
+                .zip(solution.to_line().bytes())
+                .all(|(given, cell)| given == b'.' || given == cell);
+            if solution.clue_count() != 81 || !agrees {
+                return Err(ExchangeError::Solution);
+            }
+        }
+        Ok(game)
+    }
+}
+
+impl Game {
+    /// Writes the game as a classic puzzle in the exchange format, with no solution or metadata.
+    pub fn to_exchange_json(&self) -> String {
+        let doc = Document::classic(self, None, &Metadata::default());
+        serde_json::to_string(&doc).unwrap()
+    }
+
+    /// Reads a game from an exchange document. A solution in the document is checked against the
+    /// givens but not kept, and metadata is skipped.
+    pub fn from_exchange_json(json: &str) -> Result<Game, ExchangeError> {
+        Document::read(json)?.game()
+    }
+}
+
+/// Writes a puzzle and its metadata as an exchange document on a single line, with the solution
+/// if one is given.
+pub fn record_to_exchange_json(record: &PuzzleRecord, solution: Option<&Game>) -> String {
+    let doc = Document::classic(&record.game, solution.map(Game::to_line), &record.meta);
+    serde_json::to_string(&doc).unwrap()
+}
+
+/// Reads a puzzle and its metadata from an exchange document.
+pub fn record_from_exchange_json(json: &str) -> Result<PuzzleRecord, ExchangeError> {
+    let doc = Document::read(json)?;
+    Ok(PuzzleRecord {
+        game: doc.game()?,
+        meta: Metadata {
+            author: doc.metadata.author,
+            title: doc.metadata.title,
+            source: doc.metadata.source,
+            date: doc.metadata.date,
+            difficulty: doc.metadata.difficulty,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboards::{GOLDEN_NUGGET, GOLDEN_NUGGET_SOLUTION, HARD};
+
+    fn solution() -> Game {
+        Game::new(GOLDEN_NUGGET_SOLUTION)
+    }
+
+    #[test]
+    fn classic_puzzles_round_trip() {
+        let json = Game::new(HARD).to_exchange_json();
+        assert_eq!(Game::from_exchange_json(&json).unwrap().to_array(), HARD);
+
+        let record = PuzzleRecord {
+            game: Game::new(GOLDEN_NUGGET),
+            meta: Metadata {
+                author: Some("someone".to_string()),
+                title: Some("golden nugget".to_string()),
+                source: None,
+                date: Some("2020-02-20".to_string()),
+                difficulty: Some("hard".to_string()),
+            },
+        };
+        let json = record_to_exchange_json(&record, Some(&solution()));
+        let read = record_from_exchange_json(&json).unwrap();
+        assert_eq!(read.game.to_array(), GOLDEN_NUGGET);
+        assert_eq!(read.meta, record.meta);
+        assert_eq!(record_to_exchange_json(&read, Some(&solution())), json);
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        let mut doc = serde_json::from_str::<Value>(&Game::new(HARD).to_exchange_json()).unwrap();
+        doc["author_notes"] = Value::from("from a newer writer");
+        doc["metadata"]["rating"] = Value::from(7);
+        let game = Game::from_exchange_json(&doc.to_string()).unwrap();
+        assert_eq!(game.to_array(), HARD);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let mut doc = serde_json::from_str::<Value>(&Game::new(HARD).to_exchange_json()).unwrap();
+        doc["version"] = Value::from(EXCHANGE_VERSION + 1);
+        match Game::from_exchange_json(&doc.to_string()) {
+            Err(ExchangeError::Version(Some(version))) => {
+                assert_eq!(version, EXCHANGE_VERSION + 1)
+            }
+            other => panic!("expected a version error, got {:?}", other.map(|_| ())),
+        }
+        doc.as_object_mut().unwrap().remove("version");
+        assert!(matches!(
+            Game::from_exchange_json(&doc.to_string()),
+            Err(ExchangeError::Version(None))
+        ));
+    }
+
+    // Reserved fields in use aren't dropped, so a variant never reads back as a different
+    // classic puzzle.
+    #[test]
+    fn killer_and_jigsaw_puzzles_are_rejected() {
+        let classic = serde_json::from_str::<Value>(&Game::new(HARD).to_exchange_json()).unwrap();
+        let mut killer = classic.clone();
+        killer["rules"] = serde_json::json!(["classic", "killer"]);
+        killer["cages"] = serde_json::json!([{"sum": 15, "cells": [[0, 0], [0, 1]]}]);
+        assert!(matches!(
+            Game::from_exchange_json(&killer.to_string()),
+            Err(ExchangeError::Unsupported("rules"))
+        ));
+        killer["rules"] = serde_json::json!(["classic"]);
+        assert!(matches!(
+            Game::from_exchange_json(&killer.to_string()),
+            Err(ExchangeError::Unsupported("cages"))
+        ));
+        let mut jigsaw = classic;
+        jigsaw["regions"] = Value::from((0..9).map(|y| vec![y; 9]).collect::<Vec<_>>());
+        assert!(matches!(
+            Game::from_exchange_json(&jigsaw.to_string()),
+            Err(ExchangeError::Unsupported("regions"))
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+// Cleaning up puzzles pasted from wherever people find them: other scripts' digits, assorted blank
+// markers, grid lines, quotes, and invisible characters.
+
+use std::error::Error;
+use std::fmt;
+
+/// The characters `normalize` reads as empty cells. Zeros in other scripts count as `0`.
+pub const DEFAULT_BLANKS: &[char] = &['.', '0', '*', 'x', 'X', '_', '?'];
+
+// The zero of each run of ten decimal digits read as `0`-`9`: ASCII, Arabic-Indic, extended
+// Arabic-Indic, Devanagari, Bengali, Gurmukhi, Gujarati, Oriya, Tamil, Telugu, Kannada,
+// Malayalam, Thai, Lao, Tibetan, Myanmar, Khmer, Mongolian, and full-width.
+const ZEROS: &[u32] = &[
+    0x30, 0x660, 0x6f0, 0x966, 0x9e6, 0xa66, 0xae6, 0xb66, 0xbe6, 0xc66, 0xce6, 0xd66, 0xe50,
+    0xed0, 0xf20, 0x1040, 0x17e0, 0x1810, 0xff10,
+];
+
+/// Reasons pasted text can't be made into a puzzle. Lines and columns count from 1, and columns
+/// count characters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NormalizeError {
+    /// A character that isn't a digit, a blank marker, or something that can be skipped
+    Unrecognized {
+        line: usize,
+        column: usize,
+        ch: char,
+    },
+    /// The text had a number of cells other than 81
+    Length(usize),
+}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NormalizeError::Unrecognized { line, column, ch } => write!(
+                f,
+                "can't read {:?} (U+{:04X}) at line {}, column {}",
+                ch, *ch as u32, line, column
+            ),
+            NormalizeError::Length(len) => write!(f, "expected 81 cells, found {}", len),
+        }
+    }
+}
+
+impl Error for NormalizeError {}
+
+/// Turns pasted text into the one-line format `Game` parses, with `1`-`9` for clues and `.` for
+/// empty cells, using `DEFAULT_BLANKS` for empty cells. See `normalize_with`.
+pub fn normalize(input: &str) -> Result<String, NormalizeError> {
+    normalize_with(input, DEFAULT_BLANKS)
+}
+
+/// Turns pasted text into the one-line format `Game` parses, reading any character in `blanks`
+/// as an empty cell. Digits from other scripts, full-width digits included, are read as their
+/// ASCII forms. Whitespace, line endings, invisible characters like zero-width spaces, quotes,
+/// commas, and grid lines drawn with `|`, `+`, `-`, or box-drawing characters are skipped.
+pub fn normalize_with(input: &str, blanks: &[char]) -> Result<String, NormalizeError> {
+    let mut cells = String::with_capacity(81);
+    for (line, text) in input.split('\n').enumerate() {
+        for (column, ch) in text.chars().enumerate() {
+            let ch = ascii_digit(ch).unwrap_or(ch);
+            if blanks.contains(&ch) {
+                cells.push('.');
+            } else if ('1'..='9').contains(&ch) {
+                cells.push(ch);
+            } else if !skipped(ch) {
+                return Err(NormalizeError::Unrecognized {
+                    line: line + 1,
+                    column: column + 1,
+                    ch,
+                });
+            }
+        }
+    }
+    match cells.len() {
+        81 => Ok(cells),
+        len => Err(NormalizeError::Length(len)),
+    }
+}
+
+// The ASCII digit for a decimal digit in any of the scripts in `ZEROS`.
+fn ascii_digit(ch: char) -> Option<char> {
+    let code = ch as u32;
+    ZEROS
+        .iter()
+        .find(|&&zero| (zero..zero + 10).contains(&code))
+        .map(|&zero| (b'0' + (code - zero) as u8) as char)
+}
+
+// Characters that never stand for a cell.
+fn skipped(ch: char) -> bool {
+    ch.is_whitespace()
+        || matches!(
+            ch,
+            // Zero-width spaces and joiners, direction marks, word joiners, soft hyphens, and
+            // byte order marks
+            '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{2064}' | '\u{ad}' | '\u{feff}'
+            | '"' | '\'' | '`' | '\u{2018}'..='\u{201f}' | '\u{ab}' | '\u{bb}'
+            | '|' | '+' | '-' | ',' | ';'
+            // Box drawing
+            | '\u{2500}'..='\u{257f}'
+        )
+}
@@ -0,0 +1,457 @@
+// Puzzle file formats that carry more than the board, and the metadata that goes with each
+// puzzle. Converting between formats keeps whatever metadata the target format has room for.
+
+#[cfg(feature = "serde")]
+use super::exchange::{self, ExchangeError};
+use crate::game::ParseError;
+use crate::Game;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// Information about a puzzle beyond its board. Every field is free text, kept exactly as read.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Metadata {
+    pub author: Option<String>,
+    pub title: Option<String>,
+    /// Where the puzzle came from, usually a URL
+    pub source: Option<String>,
+    pub date: Option<String>,
+    /// A difficulty assigned by whoever published the puzzle, not one worked out by this crate
+    pub difficulty: Option<String>,
+}
+
+impl Metadata {
+    fn fields(&self) -> [(&'static str, &Option<String>); 5] {
+        [
+            ("author", &self.author),
+            ("title", &self.title),
+            ("source", &self.source),
+            ("date", &self.date),
+            ("difficulty", &self.difficulty),
+        ]
+    }
+
+    /// The names of the fields that are set but that a format has no room for, so they'd be lost
+    /// by writing to it.
+    pub fn dropped_by(&self, format: Format) -> Vec<&'static str> {
+        let kept: &[&str] = match format {
+            Format::Line | Format::Sdm => &[],
+            Format::Sdk | Format::Csv => &["author", "title", "source", "date", "difficulty"],
+            #[cfg(feature = "serde")]
+            Format::Json => &["author", "title", "source", "date", "difficulty"],
+        };
+        self.fields()
+            .iter()
+            .filter(|(name, value)| value.is_some() && !kept.contains(name))
+            .map(|&(name, _)| name)
+            .collect()
+    }
+}
+
+/// A puzzle along with its metadata.
+#[derive(Clone)]
+pub struct PuzzleRecord {
+    pub game: Game,
+    pub meta: Metadata,
+}
+
+/// The file formats puzzles can be read from and written to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One puzzle per line with `.` for empty cells, and no metadata
+    Line,
+    /// SadMan Sudoku's format: `#A`-style metadata lines, then the board as 9 lines of 9 cells
+    Sdk,
+    /// One puzzle per line with `0` for empty cells, and no metadata
+    Sdm,
+    /// Comma-separated values with a header row naming the columns `puzzle`, `title`, `author`,
+    /// `source`, `date`, and `difficulty`. Only `puzzle` is required, and the order doesn't matter.
+    Csv,
+    /// One exchange document per line; see `io::exchange`
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+/// A format name that isn't one of the supported formats.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownFormat(pub String);
+
+impl fmt::Display for UnknownFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown format {:?}", self.0)
+    }
+}
+
+impl Error for UnknownFormat {}
+
+impl FromStr for Format {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "line" => Ok(Format::Line),
+            "sdk" => Ok(Format::Sdk),
+            "sdm" => Ok(Format::Sdm),
+            "csv" => Ok(Format::Csv),
+            #[cfg(feature = "serde")]
+            "json" => Ok(Format::Json),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Reasons puzzles can't be read from a file.
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    /// A puzzle that starts on this line isn't a game. Lines count from 1.
+    Puzzle {
+        line: usize,
+        error: ParseError,
+    },
+    /// A line doesn't fit the format. Lines count from 1.
+    Syntax {
+        line: usize,
+    },
+    /// The exchange document on this line can't be read. Lines count from 1.
+    #[cfg(feature = "serde")]
+    Exchange {
+        line: usize,
+        error: ExchangeError,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(e) => e.fmt(f),
+            RecordError::Puzzle { line, error } => write!(f, "line {}: {}", line, error),
+            RecordError::Syntax { line } => write!(f, "line {}: doesn't fit the format", line),
+            #[cfg(feature = "serde")]
+            RecordError::Exchange { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+impl From<io::Error> for RecordError {
+    fn from(other: io::Error) -> Self {
+        RecordError::Io(other)
+    }
+}
+
+fn parse_game(cells: &str, line: usize) -> Result<Game, RecordError> {
+    cells
+        .parse()
+        .map_err(|error| RecordError::Puzzle { line, error })
+}
+
+/// Reads every puzzle in a file of the given format.
+pub fn read_records(
+    reader: impl BufRead,
+    format: Format,
+) -> Result<Vec<PuzzleRecord>, RecordError> {
+    // Blank lines never mean anything in any of the formats.
+    let lines = reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.map(|line| (i + 1, line)))
+        .filter(|line| !matches!(line, Ok((_, line)) if line.trim().is_empty()));
+    match format {
+        Format::Line | Format::Sdm => lines
+            .map(|line| {
+                let (n, line) = line?;
+                Ok(PuzzleRecord {
+                    game: parse_game(&line, n)?,
+                    meta: Metadata::default(),
+                })
+            })
+            .collect(),
+        Format::Sdk => read_sdk(lines),
+        Format::Csv => read_csv(lines),
+        #[cfg(feature = "serde")]
+        Format::Json => lines
+            .map(|line| {
+                let (n, line) = line?;
+                exchange::record_from_exchange_json(&line)
+                    .map_err(|error| RecordError::Exchange { line: n, error })
+            })
+            .collect(),
+    }
+}
+
+fn read_sdk(
+    lines: impl Iterator<Item = io::Result<(usize, String)>>,
+) -> Result<Vec<PuzzleRecord>, RecordError> {
+    let mut records = Vec::new();
+    let mut meta = Metadata::default();
+    let mut cells = String::new();
+    let mut start = 0;
+    for line in lines {
+        let (n, line) = line?;
+        let line = line.trim();
+        if let Some(tag) = line.strip_prefix('#') {
+            if !cells.is_empty() {
+                return Err(RecordError::Syntax { line: n });
+            }
+            let (key, value) = tag.split_at(tag.chars().next().map_or(0, char::len_utf8));
+            let value = Some(value.trim().to_string());
+            match key {
+                "A" => meta.author = value,
+                "D" => meta.title = value,
+                "U" => meta.source = value,
+                "B" => meta.date = value,
+                "L" => meta.difficulty = value,
+                // Comments and anything else aren't kept.
+                _ => {}
+            }
+            continue;
+        }
+        if cells.is_empty() {
+            start = n;
+        }
+        cells.push_str(line);
+        if cells.chars().count() >= 81 {
+            records.push(PuzzleRecord {
+                game: parse_game(&cells, start)?,
+                meta: std::mem::take(&mut meta),
+            });
+            cells.clear();
+        }
+    }
+    if !cells.is_empty() {
+        return Err(RecordError::Puzzle {
+            line: start,
+            error: ParseError::Length(cells.chars().count()),
+        });
+    }
+    Ok(records)
+}
+
+// Splits a line of comma-separated values, with double quotes around values holding commas or
+// quotes, and doubled quotes inside them.
+fn split_csv(line: &str) -> Option<Vec<String>> {
+    let mut values = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        value.push('"');
+                    }
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+            if !matches!(chars.peek(), None | Some(',')) {
+                return None;
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ',' {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+        values.push(value);
+        if chars.next().is_none() {
+            return Some(values);
+        }
+    }
+}
+
+fn quote_csv(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn read_csv(
+    mut lines: impl Iterator<Item = io::Result<(usize, String)>>,
+) -> Result<Vec<PuzzleRecord>, RecordError> {
+    let (n, header) = match lines.next() {
+        Some(line) => line?,
+        None => return Ok(Vec::new()),
+    };
+    let columns = split_csv(&header).ok_or(RecordError::Syntax { line: n })?;
+    let column = |name: &str| columns.iter().position(|c| c.trim() == name);
+    let puzzle = column("puzzle").ok_or(RecordError::Syntax { line: n })?;
+    let fields = [
+        column("author"),
+        column("title"),
+        column("source"),
+        column("date"),
+        column("difficulty"),
+    ];
+    lines
+        .map(|line| {
+            let (n, line) = line?;
+            let values = split_csv(&line).ok_or(RecordError::Syntax { line: n })?;
+            let value = |i: Option<usize>| {
+                i.and_then(|i| values.get(i))
+                    .filter(|v| !v.is_empty())
+                    .cloned()
+            };
+            let cells = values.get(puzzle).ok_or(RecordError::Syntax { line: n })?;
+            Ok(PuzzleRecord {
+                game: parse_game(cells, n)?,
+                meta: Metadata {
+                    author: value(fields[0]),
+                    title: value(fields[1]),
+                    source: value(fields[2]),
+                    date: value(fields[3]),
+                    difficulty: value(fields[4]),
+                },
+            })
+        })
+        .collect()
+}
+
+/// Writes puzzles in the given format. Metadata the format has no room for is left out; see
+/// `Metadata::dropped_by`.
+pub fn write_records(
+    mut writer: impl Write,
+    format: Format,
+    records: &[PuzzleRecord],
+) -> io::Result<()> {
+    if format == Format::Csv {
+        writeln!(writer, "puzzle,title,author,source,date,difficulty")?;
+    }
+    for record in records {
+        let line = record.game.to_line();
+        let meta = &record.meta;
+        match format {
+            Format::Line => writeln!(writer, "{}", line)?,
+            Format::Sdm => writeln!(writer, "{}", line.replace('.', "0"))?,
+            Format::Sdk => {
+                let tags = [
+                    ("A", &meta.author),
+                    ("D", &meta.title),
+                    ("U", &meta.source),
+                    ("B", &meta.date),
+                    ("L", &meta.difficulty),
+                ];
+                for (tag, value) in tags.iter() {
+                    if let Some(value) = value {
+                        writeln!(writer, "#{} {}", tag, value)?;
+                    }
+                }
+                for row in line.as_bytes().chunks(9) {
+                    writeln!(writer, "{}", String::from_utf8_lossy(row))?;
+                }
+            }
+            Format::Csv => {
+                let values = [
+                    &Some(line),
+                    &meta.title,
+                    &meta.author,
+                    &meta.source,
+                    &meta.date,
+                    &meta.difficulty,
+                ];
+                let values = values
+                    .iter()
+                    .map(|v| v.as_deref().map_or(String::new(), quote_csv))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", values.join(","))?;
+            }
+            #[cfg(feature = "serde")]
+            Format::Json => writeln!(
+                writer,
+                "{}",
+                exchange::record_to_exchange_json(record, None)
+            )?,
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quote_csv, read_records, split_csv, write_records, Format, Metadata};
+    use crate::gameboards::EASY;
+    use crate::Game;
+
+    const SDK: &str = "\
+#A Smith, \"Jo\"
+#D A title, with a comma
+#C A comment, which isn't kept
+";
+
+    fn convert(text: &str, from: Format, to: Format) -> String {
+        let records = read_records(text.as_bytes(), from).unwrap();
+        let mut out = Vec::new();
+        write_records(&mut out, to, &records).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn sdk_round_trips_through_csv() {
+        let line = Game::new(EASY).to_line();
+        let mut sdk = SDK.to_string();
+        for row in line.as_bytes().chunks(9) {
+            sdk.push_str(std::str::from_utf8(row).unwrap());
+            sdk.push('\n');
+        }
+        let csv = convert(&sdk, Format::Sdk, Format::Csv);
+        assert_eq!(
+            csv,
+            format!(
+                "puzzle,title,author,source,date,difficulty\n\
+                 {},\"A title, with a comma\",\"Smith, \"\"Jo\"\"\",,,\n",
+                line
+            )
+        );
+        let records = read_records(csv.as_bytes(), Format::Csv).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].game.to_line(), line);
+        assert_eq!(
+            records[0].meta,
+            Metadata {
+                author: Some("Smith, \"Jo\"".to_string()),
+                title: Some("A title, with a comma".to_string()),
+                ..Metadata::default()
+            }
+        );
+        let back = convert(&csv, Format::Csv, Format::Sdk);
+        assert_eq!(back, sdk.replace("#C A comment, which isn't kept\n", ""));
+        assert_eq!(
+            records[0].meta.dropped_by(Format::Line),
+            ["author", "title"]
+        );
+        assert!(records[0].meta.dropped_by(Format::Sdk).is_empty());
+    }
+
+    #[test]
+    fn csv_quoting() {
+        for value in &["plain", "", "a,b", "\"quoted\"", "\"", ",\",\""] {
+            let line = format!("{},{}", quote_csv(value), quote_csv(value));
+            assert_eq!(
+                split_csv(&line),
+                Some(vec![value.to_string(), value.to_string()]),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(quote_csv("plain"), "plain");
+        assert_eq!(quote_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(
+            split_csv("a,,\"b,c\""),
+            Some(vec!["a".to_string(), String::new(), "b,c".to_string()])
+        );
+        // An unfinished quote, and text after a closing quote
+        assert_eq!(split_csv("\"a,b"), None);
+        assert_eq!(split_csv("\"a\"b,c"), None);
+    }
+}
@@ -0,0 +1,243 @@
+// A text format for the steps of a logical solve, so that a solve can be saved, shared, and played
+// back later. A replay looks like this:
+//
+//     sudoku-replay 1
+//     4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......
+//     r2c1-6 r2c3-6 # locked-candidates
+//     r1c2=1 # hidden-single
+//
+// After the version line comes the puzzle, and then one step per line: cells filled in as `rXcY=D`
+// and candidates crossed off as `rXcY-D`, with rows and columns counted from 1, followed by the
+// name of the technique that justified them.
+
+use crate::game::ParseError;
+use crate::{CellValue, Game, Step, Technique};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const HEADER: &str = "sudoku-replay 1";
+
+/// Reasons a replay can't be read or played back.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The first line isn't a replay header this version understands
+    Header,
+    /// The puzzle line isn't a game
+    Puzzle(ParseError),
+    /// A step couldn't be read. Lines count from 1, including the header and puzzle.
+    Syntax {
+        line: usize,
+    },
+    /// A step doesn't follow from the state before it. Steps count from 1.
+    IllegalStep {
+        step: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => e.fmt(f),
+            ReplayError::Header => write!(f, "not a replay file, or from a newer version"),
+            ReplayError::Puzzle(e) => write!(f, "bad puzzle: {}", e),
+            ReplayError::Syntax { line } => write!(f, "can't read the step on line {}", line),
+            ReplayError::IllegalStep { step } => {
+                write!(f, "step {} doesn't follow from the steps before it", step)
+            }
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(other: io::Error) -> Self {
+        ReplayError::Io(other)
+    }
+}
+
+/// A puzzle and the steps taken to solve it.
+#[derive(Clone)]
+pub struct Replay {
+    pub puzzle: Game,
+    pub steps: Vec<Step>,
+}
+
+impl Replay {
+    /// Plays the steps back from the puzzle, returning the state after each one. Every step is
+    /// checked against the state before it: the cells it touches have to be empty and still have
+    /// the value as a candidate, and it can't rule out every solution.
+    pub fn play(&self) -> Result<Vec<Game>, ReplayError> {
+        let mut game = self.puzzle;
+        let mut states = Vec::with_capacity(self.steps.len());
+        for (i, step) in self.steps.iter().enumerate() {
+            if !game.try_apply_step(step) {
+                return Err(ReplayError::IllegalStep { step: i + 1 });
+            }
+            states.push(game);
+        }
+        Ok(states)
+    }
+}
+
+/// Writes a step as one line of a replay, without the line ending.
+pub fn format_step(step: &Step) -> String {
+    // Replays have always been written in lower case.
+    let name = |y, x| Game::cell_name(y, x).to_lowercase();
+    let placements = step
+        .placements
+        .iter()
+        .map(|&(y, x, cv)| format!("{}={}", name(y, x), cv as u8));
+    let eliminations = step
+        .eliminations
+        .iter()
+        .map(|&(y, x, cv)| format!("{}-{}", name(y, x), cv as u8));
+    let cells = placements.chain(eliminations).collect::<Vec<_>>();
+    format!("{} # {}", cells.join(" "), step.technique.name())
+}
+
+fn parse_step(line: &str) -> Option<Step> {
+    let mut parts = line.splitn(2, '#');
+    let cells = parts.next()?;
+    let technique = Technique::from_name(parts.next()?.trim())?;
+    let mut step = Step {
+        technique,
+        placements: Vec::new(),
+        eliminations: Vec::new(),
+        chain: None,
+    };
+    for cell in cells.split_whitespace() {
+        let (name, rest) = cell.split_at_checked(4)?;
+        let (y, x) = Game::parse_cell_name(name)?;
+        let cv = match rest.as_bytes() {
+            &[_, digit] => CellValue::from_char(char::from(digit))?,
+            _ => return None,
+        };
+        match rest.as_bytes()[0] {
+            b'=' => step.placements.push((y, x, cv)),
+            b'-' => step.eliminations.push((y, x, cv)),
+            _ => return None,
+        }
+    }
+    if step.placements.is_empty() && step.eliminations.is_empty() {
+        None
+    } else {
+        Some(step)
+    }
+}
+
+/// Writes a replay of the steps taken to solve a puzzle.
+pub fn write(mut writer: impl Write, puzzle: &Game, steps: &[Step]) -> io::Result<()> {
+    writeln!(writer, "{}", HEADER)?;
+    writeln!(writer, "{}", puzzle.to_line())?;
+    for step in steps {
+        writeln!(writer, "{}", format_step(step))?;
+    }
+    writer.flush()
+}
+
+/// Reads a replay. This only checks that each line can be read; `Replay::play` checks that the
+/// steps make sense.
+pub fn read(reader: impl BufRead) -> Result<Replay, ReplayError> {
+    let mut lines = reader.lines();
+    if lines.next().transpose()?.as_deref().map(str::trim) != Some(HEADER) {
+        return Err(ReplayError::Header);
+    }
+    let puzzle = lines
+        .next()
+        .transpose()?
+        .unwrap_or_default()
+        .parse()
+        .map_err(ReplayError::Puzzle)?;
+    let mut steps = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // The header and the puzzle take up the first two lines.
+        steps.push(parse_step(&line).ok_or(ReplayError::Syntax { line: i + 3 })?);
+    }
+    Ok(Replay { puzzle, steps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboards::X_CHAIN;
+    use std::io::Cursor;
+
+    fn recorded() -> (Game, Vec<Step>, String) {
+        let puzzle = Game::new(X_CHAIN);
+        let mut game = puzzle;
+        let steps = game.solve_logical();
+        let mut out = Vec::new();
+        write(&mut out, &puzzle, &steps).unwrap();
+        (puzzle, steps, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn replays_round_trip() {
+        let (puzzle, steps, text) = recorded();
+        assert!(steps.iter().any(|step| !step.eliminations.is_empty()));
+        let replay = read(Cursor::new(&text)).unwrap();
+        assert_eq!(replay.puzzle.to_line(), puzzle.to_line());
+        assert_eq!(replay.steps, steps);
+        let states = replay.play().unwrap();
+        assert_eq!(states.len(), steps.len());
+        let mut solution = puzzle;
+        solution.solve().unwrap();
+        assert_eq!(states.last().unwrap().to_line(), solution.to_line());
+    }
+
+    #[test]
+    fn corrupt_steps_are_caught() {
+        let (_, _, text) = recorded();
+        let lines = text.lines().collect::<Vec<_>>();
+        let with_line = |n: usize, line: &str| {
+            let mut lines = lines.clone();
+            lines[n] = line;
+            lines.join("\n")
+        };
+        // Unreadable steps are reported by line.
+        for bad in &[
+            "r1c1=0 # naked-single",
+            "r1c1=5",
+            "r1c1=5 # guess",
+            "r0c1=5 # full-house",
+        ] {
+            match read(Cursor::new(with_line(2, bad))) {
+                Err(ReplayError::Syntax { line: 3 }) => {}
+                other => panic!("{:?} read as {:?}", bad, other.map(|r| r.steps)),
+            }
+        }
+        // A step that's readable but wrong is reported by step when played back.
+        let puzzle = Game::new(X_CHAIN);
+        let solution = puzzle.solved_array().unwrap();
+        let (y, x, wrong) = (0..81)
+            .map(Game::coords)
+            .find_map(|(y, x)| {
+                let right = CellValue::new(solution[y][x]).unwrap();
+                let wrong = puzzle.candidates((y, x)).iter().find(|&cv| cv != right)?;
+                Some((y, x, wrong))
+            })
+            .unwrap();
+        let step = Step {
+            technique: Technique::NakedSingle,
+            placements: vec![(y, x, wrong)],
+            eliminations: Vec::new(),
+            chain: None,
+        };
+        let replay = read(Cursor::new(with_line(2, &format_step(&step)))).unwrap();
+        assert!(matches!(
+            replay.play(),
+            Err(ReplayError::IllegalStep { step: 1 })
+        ));
+        assert!(matches!(
+            read(Cursor::new(with_line(0, "sudoku-replay 2"))),
+            Err(ReplayError::Header)
+        ));
+    }
+}
@@ -0,0 +1,272 @@
+// Summaries of a whole collection of puzzles, for vetting a collection before publishing it or
+// looking at how its clues are spread out.
+
+use super::map_batch;
+use crate::{CellValue, Difficulty, Game};
+use std::fmt;
+
+const TIERS: [Difficulty; 6] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+    Difficulty::Diabolical,
+    Difficulty::Guessing,
+];
+
+/// How many of the hardest puzzles a report lists.
+const HARDEST: usize = 5;
+
+/// A puzzle from a collection that rated among the hardest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HardPuzzle {
+    /// The puzzle in the one-line format
+    pub line: String,
+    pub difficulty: Difficulty,
+    pub score: usize,
+}
+
+/// Statistics over a collection of puzzles, from `collection_stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionStats {
+    pub puzzles: usize,
+    /// How many puzzles fell into each difficulty tier, easiest first. Every tier is listed, even
+    /// ones with no puzzles.
+    pub tiers: Vec<(Difficulty, usize)>,
+    /// How many puzzles had each number of clues, indexed by the clue count
+    pub clue_counts: [usize; 82],
+    /// Puzzles the logical techniques can finish without guessing
+    pub logically_solvable: usize,
+    /// The mean and median number of states the backtracking search went through per puzzle
+    pub mean_nodes: f64,
+    pub median_nodes: f64,
+    /// The hardest puzzles, by difficulty tier and then by rating score, hardest first
+    pub hardest: Vec<HardPuzzle>,
+}
+
+// What gets worked out for each puzzle.
+struct Analysis {
+    clues: usize,
+    difficulty: Difficulty,
+    score: usize,
+    nodes: usize,
+}
+
+fn analyze(game: &Game) -> Analysis {
+    let rating = game.rate();
+    let mut nodes = 0;
+    // Unsolvable puzzles still count for however much searching it took to find that out.
+    let _ = game.clone().solve_with_callback(|_, _| nodes += 1);
+    Analysis {
+        clues: game.clue_count(),
+        difficulty: rating.difficulty,
+        score: rating.score,
+        nodes,
+    }
+}
+
+/// Rates and solves every game, and sums up the results. With the `parallel` feature, the games
+/// are spread across threads.
+pub fn collection_stats(games: &[Game]) -> CollectionStats {
+    let analyses = map_batch(games, analyze);
+    let mut clue_counts = [0; 82];
+    for analysis in &analyses {
+        clue_counts[analysis.clues] += 1;
+    }
+    let mut nodes = analyses.iter().map(|a| a.nodes).collect::<Vec<_>>();
+    nodes.sort_unstable();
+    let mean_nodes = match nodes.len() {
+        0 => 0.0,
+        n => nodes.iter().sum::<usize>() as f64 / n as f64,
+    };
+    let median_nodes = match nodes.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => nodes[n / 2] as f64,
+        n => (nodes[n / 2 - 1] + nodes[n / 2]) as f64 / 2.0,
+    };
+    // A puzzle that needs guessing stops scoring where the logical solver got stuck, so the tier
+    // goes first. Stable sorting keeps earlier puzzles first among ties.
+    let mut by_score = (0..games.len()).collect::<Vec<_>>();
+    by_score.sort_by_key(|&i| std::cmp::Reverse((analyses[i].difficulty, analyses[i].score)));
+    CollectionStats {
+        puzzles: games.len(),
+        tiers: TIERS
+            .iter()
+            .map(|&tier| {
+                let count = analyses.iter().filter(|a| a.difficulty == tier).count();
+                (tier, count)
+            })
+            .collect(),
+        clue_counts,
+        logically_solvable: analyses
+            .iter()
+            .filter(|a| a.difficulty != Difficulty::Guessing)
+            .count(),
+        mean_nodes,
+        median_nodes,
+        hardest: by_score
+            .into_iter()
+            .take(HARDEST)
+            .map(|i| HardPuzzle {
+                line: games[i].to_line(),
+                difficulty: analyses[i].difficulty,
+                score: analyses[i].score,
+            })
+            .collect(),
+    }
+}
+
+impl CollectionStats {
+    /// The share of puzzles the logical techniques can finish, as a percentage.
+    pub fn logical_percent(&self) -> f64 {
+        match self.puzzles {
+            0 => 0.0,
+            n => 100.0 * self.logically_solvable as f64 / n as f64,
+        }
+    }
+
+    /// Writes the statistics as a JSON object. The keys are `puzzles`, `tiers` (an object from
+    /// tier name to count), `clue_counts` (an object from clue count to number of puzzles, leaving
+    /// out counts no puzzle had), `logically_solvable`, `logical_percent`, `mean_nodes`,
+    /// `median_nodes`, and `hardest` (an array of objects with `line`, `difficulty`, and
+    /// `score`).
+    pub fn to_json(&self) -> String {
+        let tiers = self
+            .tiers
+            .iter()
+            .map(|(tier, count)| format!("\"{:?}\": {}", tier, count))
+            .collect::<Vec<_>>();
+        let clue_counts = self
+            .clue_counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(clues, count)| format!("\"{}\": {}", clues, count))
+            .collect::<Vec<_>>();
+        let hardest = self
+            .hardest
+            .iter()
+            .map(|puzzle| {
+                format!(
+                    "{{\"line\": \"{}\", \"difficulty\": \"{:?}\", \"score\": {}}}",
+                    puzzle.line, puzzle.difficulty, puzzle.score
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{{\"puzzles\": {}, \"tiers\": {{{}}}, \"clue_counts\": {{{}}}, \
+             \"logically_solvable\": {}, \"logical_percent\": {:.1}, \"mean_nodes\": {:.1}, \
+             \"median_nodes\": {:.1}, \"hardest\": [{}]}}",
+            self.puzzles,
+            tiers.join(", "),
+            clue_counts.join(", "),
+            self.logically_solvable,
+            self.logical_percent(),
+            self.mean_nodes,
+            self.median_nodes,
+            hardest.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for CollectionStats {
+    /// A plain text report, one figure per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "puzzles: {}", self.puzzles)?;
+        writeln!(f, "tiers:")?;
+        for (tier, count) in &self.tiers {
+            writeln!(f, "  {:<10} {}", format!("{:?}", tier), count)?;
+        }
+        writeln!(f, "clues:")?;
+        for (clues, &count) in self.clue_counts.iter().enumerate() {
+            if count > 0 {
+                writeln!(f, "  {:>2} {}", clues, count)?;
+            }
+        }
+        writeln!(
+            f,
+            "solvable without guessing: {} ({:.1}%)",
+            self.logically_solvable,
+            self.logical_percent()
+        )?;
+        writeln!(
+            f,
+            "search nodes: mean {:.1}, median {:.1}",
+            self.mean_nodes, self.median_nodes
+        )?;
+        writeln!(f, "hardest:")?;
+        for puzzle in &self.hardest {
+            writeln!(
+                f,
+                "  {} {:?} {}",
+                puzzle.line, puzzle.difficulty, puzzle.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the clues fall across a collection of puzzles, from `heatmap`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Heatmap {
+    pub puzzles: u32,
+    /// How many puzzles have a clue in each cell, as rows of columns
+    pub given_counts: [[u32; 9]; 9],
+    /// How many clues there are of each value, over every puzzle, with 1 first
+    pub digit_counts: [u32; 9],
+}
+
+/// Counts where the clues are and what they are over a collection of puzzles. Each puzzle is
+/// looked at once and then dropped, so this works on collections of any size.
+pub fn heatmap(puzzles: impl IntoIterator<Item = Game>) -> Heatmap {
+    let mut heatmap = Heatmap::default();
+    for game in puzzles {
+        heatmap.puzzles += 1;
+        for (y, row) in game.to_array().iter().enumerate() {
+            for (x, &n) in row.iter().enumerate() {
+                if n != 0 {
+                    heatmap.given_counts[y][x] += 1;
+                    heatmap.digit_counts[usize::from(n) - 1] += 1;
+                }
+            }
+        }
+    }
+    heatmap
+}
+
+impl Heatmap {
+    /// Writes the counts as CSV, in two tables separated by a blank line: the clue counts as a
+    /// 9x9 grid with `r1`-`r9` and `c1`-`c9` headings, then a `digit,count` table.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(",c1,c2,c3,c4,c5,c6,c7,c8,c9\n");
+        for (y, row) in self.given_counts.iter().enumerate() {
+            let cells = row.iter().map(u32::to_string).collect::<Vec<_>>();
+            out.push_str(&format!("r{},{}\n", y + 1, cells.join(",")));
+        }
+        out.push_str("\ndigit,count\n");
+        for (&cv, count) in CellValue::ALL.iter().zip(&self.digit_counts) {
+            out.push_str(&format!("{},{}\n", cv as u8, count));
+        }
+        out
+    }
+
+    /// Writes the counts as a JSON object with the keys `puzzles`, `given_counts` (an array of
+    /// rows, each an array of counts), and `digit_counts` (an array of counts, with 1 first).
+    pub fn to_json(&self) -> String {
+        let list = |counts: &[u32]| {
+            let counts = counts.iter().map(u32::to_string).collect::<Vec<_>>();
+            format!("[{}]", counts.join(", "))
+        };
+        let rows = self
+            .given_counts
+            .iter()
+            .map(|row| list(row))
+            .collect::<Vec<_>>();
+        format!(
+            "{{\"puzzles\": {}, \"given_counts\": [{}], \"digit_counts\": {}}}",
+            self.puzzles,
+            rows.join(", "),
+            list(&self.digit_counts)
+        )
+    }
+}
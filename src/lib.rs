@@ -0,0 +1,18 @@
+mod benchmark;
+pub mod game;
+pub mod gameboards;
+mod handle;
+pub mod io;
+
+pub use benchmark::{self_benchmark, SelfBenchReport, WORKLOAD_VERSION};
+pub use game::{
+    band_completions, quick_validate, Band, BoardDisplay, BoardError, Candidates, CellValue,
+    ChainLimits, ClueReport, Contradiction, Coord, Difficulty, DigitArray, DigitSummary,
+    DraftBoard, ElimError, EraseError, ForcingChain, Game, GameBuilder, HardnessEstimate, Hint,
+    HintBudget, HintKind, HintOutcome, HintsExhausted, MaskError, PlaceError, PositionMask,
+    Preview, PropagationReport, Puzzle, PuzzleEditor, Rating, RestrictedSolve, Reveal, RevealError,
+    SaveState, SearchNode, Solution, Solutions, SolveError, SolverConfig, SolverPipeline,
+    SplitMix64, StateBlob, Status, Step, SudokuRng, Technique, TechniqueSet, Transform,
+    UnknownTechnique, Violation,
+};
+pub use handle::{spawn_solve, Progress, SolveHandle};
@@ -1,17 +1,611 @@
-mod game;
-#[allow(dead_code)]
-mod gameboards;
-
-use game::Game;
-use gameboards::*;
-use std::time::Instant;
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+use sudoku::gameboards::*;
+use sudoku::io::records::{self, Format, UnknownFormat};
+use sudoku::io::{self as sio, collection, dedup as sdedup, replay, stats as sstats, BatchOptions};
+use sudoku::{Game, Puzzle, TechniqueSet};
 
 fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        None => demo(),
+        Some("batch") => batch(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("dedup") => dedup(&args[1..]),
+        Some("rate") => rate(&args[1..]),
+        Some("record") => record(&args[1..]),
+        Some("replay") => play_replay(&args[1..]),
+        Some("sample") => sample(&args[1..]),
+        Some("stats") => stats(&args[1..]),
+        Some(other) => fail(&format!("unknown command {:?}", other)),
+    }
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("error: {}", msg);
+    process::exit(1);
+}
+
+// Reads one game, or a `Puzzle` or anything else that parses the same way, per non-blank line of
+// stdin.
+fn read_games<T: FromStr>() -> Vec<T>
+where
+    T::Err: fmt::Display,
+{
+    io::stdin()
+        .lock()
+        .lines()
+        .map(|line| line.unwrap_or_else(|e| fail(&e.to_string())))
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .unwrap_or_else(|e| fail(&format!("line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+fn demo() {
     let mut game = Game::new(SEVENTEEN);
     println!("{}", game);
     let start = Instant::now();
-    game.solve();
+    game.solve().unwrap_or_else(|e| fail(&e.to_string()));
     let elapsed = start.elapsed();
     println!("{}", game);
     println!("Time taken: {:?}", elapsed);
 }
+
+// sudoku convert [--from FORMAT] --to FORMAT [--name NAME] < games.txt
+fn convert(args: &[String]) {
+    let mut from = Format::Line;
+    let mut to = None;
+    let mut name = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => {
+                from = args
+                    .next()
+                    .unwrap_or_else(|| fail("--from needs a format"))
+                    .parse()
+                    .unwrap_or_else(|e: UnknownFormat| fail(&e.to_string()));
+            }
+            "--to" => to = args.next(),
+            "--name" => name = args.next(),
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let records =
+        records::read_records(io::stdin().lock(), from).unwrap_or_else(|e| fail(&e.to_string()));
+    let to = to.unwrap_or_else(|| fail("convert needs a --to format"));
+    if to == "rust-const" {
+        let name = name.unwrap_or_else(|| fail("--to rust-const needs a --name"));
+        warn_dropped(
+            records
+                .iter()
+                .map(|record| record.meta.dropped_by(Format::Line)),
+        );
+        for (i, record) in records.iter().enumerate() {
+            // Number any boards after the first so the names don't collide.
+            let name = match i {
+                0 => name.clone(),
+                _ => format!("{}_{}", name, i + 1),
+            };
+            print!("{}", record.game.to_rust_const(&name));
+        }
+        return;
+    }
+    let to = to
+        .parse()
+        .unwrap_or_else(|e: UnknownFormat| fail(&e.to_string()));
+    warn_dropped(records.iter().map(|record| record.meta.dropped_by(to)));
+    records::write_records(io::stdout().lock(), to, &records)
+        .unwrap_or_else(|e| fail(&e.to_string()));
+}
+
+// Warns about metadata fields the output format couldn't hold, given the fields dropped from each
+// record.
+fn warn_dropped(dropped: impl Iterator<Item = Vec<&'static str>>) {
+    let mut fields = Vec::new();
+    let mut count = 0;
+    for record in dropped.filter(|fields| !fields.is_empty()) {
+        count += 1;
+        for field in record {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+    }
+    if count > 0 {
+        eprintln!(
+            "warning: dropped {} from {} puzzle{}, which the output format can't hold",
+            fields.join(", "),
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+    }
+}
+
+// sudoku bench --self
+fn bench(args: &[String]) {
+    let mut own = false;
+    for arg in args {
+        match arg.as_str() {
+            "--self" => own = true,
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    if !own {
+        fail("bench needs --self");
+    }
+    print!("{}", sudoku::self_benchmark());
+}
+
+// sudoku check [--report] < games.txt
+fn check(args: &[String]) {
+    let mut report = false;
+    for arg in args {
+        match arg.as_str() {
+            "--report" => report = true,
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    for puzzle in read_games::<Puzzle>() {
+        let game = puzzle.game();
+        let clues = game.clue_report();
+        // Two missing values already rule out a unique solution, which `count_solutions` knows
+        // as soon as it finds the first.
+        let status = match game.count_solutions(2) {
+            0 => "no solution",
+            1 => "ok",
+            _ => "multiple solutions",
+        };
+        println!("{}", status);
+        if !report {
+            continue;
+        }
+        let counts = |counts: &[usize; 9]| {
+            counts
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        println!("  digits: {}", counts(&clues.digit_counts));
+        println!("  rows:   {}", counts(&clues.row_counts));
+        println!("  cols:   {}", counts(&clues.col_counts));
+        println!("  boxes:  {}", counts(&clues.box_counts));
+        if let Some(((top, left), (bottom, right))) = clues.bounds {
+            println!(
+                "  bounds: {} to {}",
+                Game::cell_name(top, left),
+                Game::cell_name(bottom, right)
+            );
+        }
+        if !clues.missing_digits.is_empty() {
+            let missing = clues
+                .missing_digits
+                .iter()
+                .map(|&cv| (cv as u8).to_string())
+                .collect::<Vec<_>>();
+            println!("  missing digits: {}", missing.join(" "));
+        }
+        if clues.has_empty_unit() {
+            println!("  some row, column, or 3x3 has no clues");
+        }
+    }
+}
+
+// sudoku batch [--flush-every N] < games.txt > solutions.txt
+fn batch(args: &[String]) {
+    let mut opts = BatchOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--flush-every" => {
+                opts.flush_every = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| fail("--flush-every needs a number"));
+            }
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let summary = sio::stream_solve_with_errors(stdin.lock(), stdout.lock(), io::stderr(), &opts)
+        .unwrap_or_else(|e| fail(&e.to_string()));
+    eprintln!(
+        "{} read, {} solved, {} malformed, {} unsolvable",
+        summary.read, summary.solved, summary.malformed, summary.unsolvable
+    );
+}
+
+// sudoku rate [--allow singles,pairs,locked] < games.txt
+fn rate(args: &[String]) {
+    let mut allow = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--allow" => {
+                let list = args
+                    .next()
+                    .unwrap_or_else(|| fail("--allow needs a list of techniques"));
+                allow = Some(
+                    list.parse::<TechniqueSet>()
+                        .unwrap_or_else(|e| fail(&e.to_string())),
+                );
+            }
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    for game in read_games::<Game>() {
+        let allow = match allow {
+            Some(allow) => allow,
+            None => {
+                println!("{:?}", game.difficulty());
+                continue;
+            }
+        };
+        let result = game.restricted_solve(&allow);
+        if result.solved {
+            println!("solved");
+        } else {
+            match result.unblocked_by {
+                Some(technique) => println!(
+                    "stuck with {} cells left, needs {}",
+                    result.remaining, technique
+                ),
+                None => println!("stuck with {} cells left, needs guessing", result.remaining),
+            }
+        }
+    }
+}
+
+// sudoku record < game.txt > solve.replay
+fn record(args: &[String]) {
+    if let Some(other) = args.first() {
+        fail(&format!("unknown option {:?}", other));
+    }
+    let puzzle = read_games::<Game>()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| fail("no game to record"));
+    let mut game = puzzle;
+    let steps = game.solve_logical();
+    replay::write(io::stdout().lock(), &puzzle, &steps).unwrap_or_else(|e| fail(&e.to_string()));
+}
+
+// Reads a delay like `100ms`, `2s`, or a bare number of milliseconds.
+fn parse_delay(s: &str) -> Option<Duration> {
+    if let Some(ms) = s.strip_suffix("ms") {
+        ms.parse().ok().map(Duration::from_millis)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        secs.parse()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+    } else {
+        s.parse().ok().map(Duration::from_millis)
+    }
+}
+
+// sudoku replay FILE [--delay 100ms]
+fn play_replay(args: &[String]) {
+    let mut path = None;
+    let mut delay = Duration::from_millis(100);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delay" => {
+                delay = args
+                    .next()
+                    .and_then(|d| parse_delay(d))
+                    .unwrap_or_else(|| fail("--delay needs a duration like 100ms"));
+            }
+            other if path.is_none() && !other.starts_with("--") => path = Some(other),
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("replay needs a file"));
+    let file = File::open(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    let recorded =
+        replay::read(BufReader::new(file)).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    // Check every step before showing any of them.
+    let states = recorded
+        .play()
+        .unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    println!("{}", recorded.puzzle);
+    for (i, (step, state)) in recorded.steps.iter().zip(&states).enumerate() {
+        thread::sleep(delay);
+        if !delay.is_zero() {
+            // Clear the screen so the board redraws in place.
+            print!("\x1b[2J\x1b[H");
+        }
+        println!(
+            "step {}/{}: {}",
+            i + 1,
+            states.len(),
+            replay::format_step(step)
+        );
+        println!("{}", state);
+    }
+}
+
+// sudoku stats FILE [--format FORMAT] [--json] [--heatmap OUT]
+fn stats(args: &[String]) {
+    let mut path = None;
+    let mut format = None;
+    let mut json = false;
+    let mut heatmap = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--heatmap" => {
+                heatmap = Some(
+                    args.next()
+                        .unwrap_or_else(|| fail("--heatmap needs a file")),
+                );
+            }
+            "--format" => {
+                format = Some(
+                    args.next()
+                        .unwrap_or_else(|| fail("--format needs a format"))
+                        .parse()
+                        .unwrap_or_else(|e: UnknownFormat| fail(&e.to_string())),
+                );
+            }
+            other if path.is_none() && !other.starts_with("--") => path = Some(other),
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("stats needs a file"));
+    let format = format.unwrap_or_else(|| format_of(path));
+    let games = read_file(path, format)
+        .into_iter()
+        .map(|record| record.game)
+        .collect::<Vec<_>>();
+    let report = sstats::collection_stats(&games);
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report);
+    }
+    if let Some(out) = heatmap {
+        // CSV unless the file is named as JSON.
+        let heatmap = sstats::heatmap(games);
+        let text = if out.ends_with(".json") {
+            heatmap.to_json() + "\n"
+        } else {
+            heatmap.to_csv()
+        };
+        std::fs::write(out, text).unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+    }
+}
+
+// sudoku dedup FILE -o OUT [--format FORMAT] [--report FILE]
+fn dedup(args: &[String]) {
+    let mut path = None;
+    let mut out = None;
+    let mut report = None;
+    let mut format = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => out = Some(args.next().unwrap_or_else(|| fail("-o needs a file"))),
+            "--report" => {
+                report = Some(args.next().unwrap_or_else(|| fail("--report needs a file")));
+            }
+            "--format" => {
+                format = Some(
+                    args.next()
+                        .unwrap_or_else(|| fail("--format needs a format"))
+                        .parse()
+                        .unwrap_or_else(|e: UnknownFormat| fail(&e.to_string())),
+                );
+            }
+            other if path.is_none() && !other.starts_with('-') => path = Some(other),
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("dedup needs a file"));
+    let out = out.unwrap_or_else(|| fail("dedup needs an output file given with -o"));
+    let format = format.unwrap_or_else(|| format_of(path));
+    let records = read_file(path, format);
+    let result = sdedup::dedup(records.iter().map(|record| record.game));
+    // The output goes by its own extension, unless it doesn't name a format.
+    let out_format = out
+        .rsplit_once('.')
+        .and_then(|(_, ext)| ext.parse().ok())
+        .unwrap_or(format);
+    let kept = result
+        .kept
+        .iter()
+        .map(|&(i, _)| records[i].clone())
+        .collect::<Vec<_>>();
+    warn_dropped(kept.iter().map(|record| record.meta.dropped_by(out_format)));
+    let file = File::create(out).unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+    records::write_records(BufWriter::new(file), out_format, &kept)
+        .unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+    if let Some(report) = report {
+        let file = File::create(report).unwrap_or_else(|e| fail(&format!("{}: {}", report, e)));
+        let mut file = BufWriter::new(file);
+        for dup in &result.duplicates {
+            writeln!(
+                file,
+                "puzzle {} is a copy of puzzle {}: {}",
+                dup.index + 1,
+                dup.representative + 1,
+                records[dup.index].game.to_line()
+            )
+            .unwrap_or_else(|e| fail(&format!("{}: {}", report, e)));
+        }
+        file.flush()
+            .unwrap_or_else(|e| fail(&format!("{}: {}", report, e)));
+    }
+    eprintln!(
+        "{} read, {} kept, {} duplicates",
+        records.len(),
+        result.kept.len(),
+        result.duplicates.len()
+    );
+}
+
+// sudoku sample FILE (--n N | --split A/B) -o OUT [-o OUT] [--seed S] [--stratify-by difficulty]
+//     [--format FORMAT]
+fn sample(args: &[String]) {
+    let mut path = None;
+    let mut outs = Vec::new();
+    let mut n = None;
+    let mut split = None;
+    let mut seed = 0;
+    let mut stratify = false;
+    let mut format = None;
+    let mut args = args.iter();
+    let number = |arg: Option<&String>, option: &str| -> u64 {
+        arg.and_then(|n| n.parse().ok())
+            .unwrap_or_else(|| fail(&format!("{} needs a number", option)))
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => outs.push(args.next().unwrap_or_else(|| fail("-o needs a file"))),
+            "--n" => n = Some(number(args.next(), "--n") as usize),
+            "--seed" => seed = number(args.next(), "--seed"),
+            "--split" => {
+                let ratio = args
+                    .next()
+                    .and_then(|ratio| ratio.split_once('/'))
+                    .and_then(|(a, b)| Some((a.parse::<usize>().ok()?, b.parse::<usize>().ok()?)))
+                    .filter(|&(a, b)| a + b > 0)
+                    .unwrap_or_else(|| fail("--split needs a ratio like 80/20"));
+                split = Some(ratio);
+            }
+            "--stratify-by" => match args.next().map(String::as_str) {
+                Some("difficulty") => stratify = true,
+                Some(other) => fail(&format!("can't stratify by {:?}", other)),
+                None => fail("--stratify-by needs a key"),
+            },
+            "--format" => {
+                format = Some(
+                    args.next()
+                        .unwrap_or_else(|| fail("--format needs a format"))
+                        .parse()
+                        .unwrap_or_else(|e: UnknownFormat| fail(&e.to_string())),
+                );
+            }
+            other if path.is_none() && !other.starts_with('-') => path = Some(other),
+            other => fail(&format!("unknown option {:?}", other)),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("sample needs a file"));
+    let format = format.unwrap_or_else(|| format_of(path));
+    let records = read_file(path, format);
+    let n = match (n, split, outs.len()) {
+        (Some(n), None, 1) => n,
+        (None, Some((a, b)), 2) => (records.len() * a + (a + b) / 2) / (a + b),
+        (None, None, _) => fail("sample needs --n or --split"),
+        (Some(_), Some(_), _) => fail("--n and --split can't be used together"),
+        (Some(_), None, _) => fail("sample needs one output file given with -o"),
+        (None, Some(_), _) => fail("--split needs two output files given with -o"),
+    };
+    // A difficulty the file gives is used as it is, and the other puzzles are rated.
+    let tiers = if stratify {
+        let unrated = records
+            .iter()
+            .filter(|record| record.meta.difficulty.is_none())
+            .map(|record| record.game)
+            .collect::<Vec<_>>();
+        let mut rated = sio::rate_many(&unrated).into_iter();
+        records
+            .iter()
+            .map(|record| match &record.meta.difficulty {
+                Some(difficulty) => difficulty.clone(),
+                None => format!("{:?}", rated.next().unwrap()),
+            })
+            .collect()
+    } else {
+        vec![String::new(); records.len()]
+    };
+    let (first, second) = collection::stratified_split(
+        records.iter().zip(&tiers),
+        |&(_, tier)| tier.clone(),
+        n,
+        seed,
+    );
+    for (part, out) in [&first, &second].iter().zip(&outs) {
+        let out_format = out
+            .rsplit_once('.')
+            .and_then(|(_, ext)| ext.parse().ok())
+            .unwrap_or(format);
+        let part = part
+            .iter()
+            .map(|&(record, _)| record.clone())
+            .collect::<Vec<_>>();
+        warn_dropped(part.iter().map(|record| record.meta.dropped_by(out_format)));
+        let file = File::create(out).unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+        records::write_records(BufWriter::new(file), out_format, &part)
+            .unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+    }
+    if stratify {
+        let mut counts = std::collections::BTreeMap::<&str, (usize, usize)>::new();
+        for tier in &tiers {
+            counts.entry(tier).or_default().1 += 1;
+        }
+        for (_, tier) in &first {
+            counts.entry(tier).or_default().0 += 1;
+        }
+        for (tier, (picked, total)) in counts {
+            eprintln!("{}: {} of {}", tier, picked, total);
+        }
+    }
+    if split.is_some() {
+        eprintln!(
+            "{} read, split {} / {}",
+            records.len(),
+            first.len(),
+            second.len()
+        );
+    } else {
+        eprintln!("{} read, {} sampled", records.len(), first.len());
+    }
+}
+
+// Guesses a file's format from its extension, falling back on one puzzle per line.
+fn format_of(path: &str) -> Format {
+    path.rsplit_once('.')
+        .and_then(|(_, ext)| ext.parse().ok())
+        .unwrap_or(Format::Line)
+}
+
+fn read_file(path: &str, format: Format) -> Vec<records::PuzzleRecord> {
+    let file = File::open(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    records::read_records(BufReader::new(file), format)
+        .unwrap_or_else(|e| fail(&format!("{}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_parse() {
+        assert_eq!(parse_delay("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_delay("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_delay("40"), Some(Duration::from_millis(40)));
+    }
+
+    #[test]
+    fn bad_delays_are_rejected() {
+        for delay in &["-1s", "NaNs", "infs", "1e30s", "-5ms", "fast"] {
+            assert_eq!(parse_delay(delay), None, "{}", delay);
+        }
+    }
+}
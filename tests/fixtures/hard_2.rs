@@ -0,0 +1,11 @@
+pub const HARD_2: [[u8; 9]; 9] = [
+    [8, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 5, 3, 2, 0, 0, 9, 0, 0],
+    [0, 0, 4, 0, 1, 5, 0, 0, 8],
+    [9, 0, 0, 7, 5, 4, 0, 1, 0],
+    [0, 3, 1, 0, 0, 0, 4, 0, 0],
+    [5, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 4, 8, 0, 0, 3],
+    [0, 0, 0, 5, 0, 0, 0, 6, 2],
+    [0, 7, 5, 6, 0, 0, 0, 0, 0],
+];
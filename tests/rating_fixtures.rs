@@ -0,0 +1,104 @@
+use sudoku::{Game, Technique};
+
+const FIXTURES: &str = include_str!("fixtures/ratings.txt");
+
+fn position(technique: Technique) -> usize {
+    Technique::ALL.iter().position(|&t| t == technique).unwrap()
+}
+
+#[test]
+fn ratings_match_fixtures() {
+    let mut checked = 0;
+    for (i, line) in FIXTURES.lines().enumerate() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let (puzzle, tier, hardest) = match fields[..] {
+            [puzzle, tier, hardest] => (puzzle, tier, hardest),
+            _ => panic!("line {}: expected a puzzle, a tier, and a technique", i + 1),
+        };
+        let game = puzzle.parse::<Game>().unwrap();
+        let rating = game.rate();
+        let got = format!("{:?}", rating.difficulty).to_lowercase();
+        assert_eq!(got, tier, "line {}: {}", i + 1, puzzle);
+        match (hardest, rating.hardest_technique) {
+            ("-", None) => {}
+            ("-", Some(technique)) => {
+                panic!("line {}: expected no technique, got {}", i + 1, technique)
+            }
+            (expected, got) => {
+                let expected = expected.parse::<Technique>().unwrap();
+                let got = got.unwrap_or_else(|| panic!("line {}: no technique used", i + 1));
+                assert!(
+                    position(expected).abs_diff(position(got)) <= 1,
+                    "line {}: expected {} as the hardest technique, got {}",
+                    i + 1,
+                    expected,
+                    got
+                );
+            }
+        }
+        checked += 1;
+    }
+    assert_eq!(checked, 30);
+}
+
+// Spearman's rank correlation, with tied values sharing the average of their ranks.
+fn rank_correlation(a: &[f64], b: &[f64]) -> f64 {
+    fn ranks(values: &[f64]) -> Vec<f64> {
+        values
+            .iter()
+            .map(|&v| {
+                let below = values.iter().filter(|&&w| w < v).count();
+                let equal = values.iter().filter(|&&w| w == v).count();
+                below as f64 + (equal - 1) as f64 / 2.0
+            })
+            .collect()
+    }
+    let (a, b) = (ranks(a), ranks(b));
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let cov = a
+        .iter()
+        .zip(&b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum::<f64>();
+    let var_a = a.iter().map(|x| (x - mean_a).powi(2)).sum::<f64>();
+    let var_b = b.iter().map(|y| (y - mean_b).powi(2)).sum::<f64>();
+    cov / (var_a * var_b).sqrt()
+}
+
+#[test]
+fn hardness_estimate_follows_the_rating() {
+    let games = FIXTURES
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| {
+            line.split_whitespace()
+                .next()
+                .unwrap()
+                .parse::<Game>()
+                .unwrap()
+        })
+        .chain(
+            sudoku::gameboards::with_solutions()
+                .iter()
+                .map(|named| Game::new(named.board)),
+        )
+        .collect::<Vec<_>>();
+    let estimates = games
+        .iter()
+        .map(|game| game.estimate_hardness().log_search_space)
+        .collect::<Vec<_>>();
+    let tiers = games
+        .iter()
+        .map(|game| game.difficulty() as usize as f64)
+        .collect::<Vec<_>>();
+    let correlation = rank_correlation(&estimates, &tiers);
+    assert!(
+        (0.2..0.8).contains(&correlation),
+        "rank correlation {}",
+        correlation
+    );
+}
@@ -0,0 +1,26 @@
+use sudoku::gameboards::HARD;
+use sudoku::Game;
+
+// Compiled here so that `cargo fmt --check` holds the fixture to rustfmt's formatting, which
+// `to_rust_const` then has to match byte for byte.
+#[path = "fixtures/hard_2.rs"]
+mod hard_2;
+
+const PUZZLE: &str =
+    "8.........532..9....4.15..89..754.1..31...4..5............48..3...5...62.756.....";
+
+#[test]
+fn rust_consts_match_the_fixture() {
+    let game = PUZZLE.parse::<Game>().unwrap();
+    assert_eq!(
+        game.to_rust_const("hard 2"),
+        include_str!("fixtures/hard_2.rs")
+    );
+    assert_eq!(Game::new(hard_2::HARD_2).to_array(), game.to_array());
+}
+
+#[test]
+fn rust_consts_match_gameboards() {
+    let source = include_str!("../src/gameboards.rs");
+    assert!(source.contains(&Game::new(HARD).to_rust_const("hard")));
+}